@@ -0,0 +1,37 @@
+use std::fs;
+
+use aoc::{data_path, registry::DAYS};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+/// One group per day with parse, part 1 and part 2 benchmarked separately on the real
+/// input. Run a single day with e.g. `cargo bench -p aoc -- day15/`.
+fn solvers(c: &mut Criterion) {
+    for day in DAYS {
+        let Ok(input) = fs::read_to_string(data_path(day.day, "input")) else {
+            continue;
+        };
+
+        let mut group = c.benchmark_group(format!("day{}", day.day));
+        group.sample_size(10);
+
+        group.bench_function("parse", |b| {
+            b.iter(|| day.solver.parse(black_box(input.as_str())))
+        });
+
+        let Ok(parsed) = day.solver.parse(input.as_str()) else {
+            group.finish();
+            continue;
+        };
+
+        for part in [1, 2] {
+            group.bench_function(format!("part{part}"), |b| {
+                b.iter(|| day.part(part, black_box(parsed.as_ref())))
+            });
+        }
+
+        group.finish();
+    }
+}
+
+criterion_group!(benches, solvers);
+criterion_main!(benches);
@@ -0,0 +1,15 @@
+pub mod registry;
+
+use std::path::PathBuf;
+
+pub fn data_path(day: u32, file: &str) -> PathBuf {
+    [
+        env!("CARGO_MANIFEST_DIR"),
+        "..",
+        &format!("day{day}"),
+        "data",
+        file,
+    ]
+    .iter()
+    .collect()
+}
@@ -0,0 +1,305 @@
+#![feature(is_terminal)]
+
+mod timing;
+mod verify;
+
+use std::{
+    fs,
+    io::{self, IsTerminal},
+    path::PathBuf,
+    process::ExitCode,
+};
+
+use anyhow::{anyhow, Context, Result};
+use aoc::{
+    data_path,
+    registry::{DAYS, SOLUTIONS},
+};
+use clap::{Args, Parser, Subcommand};
+use day16::{get_dot, DotGraph};
+use util::{
+    input::{HttpFetcher, InputCache},
+    read_input_as_string, ParseReport,
+};
+
+const YEAR: u32 = 2022;
+
+#[derive(Debug, Parser)]
+#[command(about = "Runs Advent of Code 2022 solutions")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Run solvers for a single day (input from stdin) or for every day
+    Run(RunArgs),
+    /// Run solvers against every input with a known answer and report mismatches
+    Verify(VerifyArgs),
+    /// Export the day 16 valve network as Graphviz DOT (input from stdin or the input cache)
+    Valves(ValvesArgs),
+}
+
+#[derive(Debug, Args)]
+struct RunArgs {
+    /// Day to run, input is read from stdin or, if nothing is piped in, from the input cache
+    #[arg(short, long, required_unless_present = "all")]
+    day: Option<u32>,
+
+    /// Part to run, both parts are run if omitted
+    #[arg(short, long, requires = "day")]
+    part: Option<u32>,
+
+    /// Run every registered solver against its `dayN/data/input`
+    #[arg(long, conflicts_with = "day")]
+    all: bool,
+
+    /// Directory inputs are cached in, defaults to `$AOC_CACHE_DIR` or `~/.cache/aoc`.
+    /// Missing inputs are downloaded using the session in `$AOC_SESSION`
+    #[arg(long)]
+    cache_dir: Option<PathBuf>,
+
+    /// Parse once and report parse, part 1 and part 2 wall-clock times separately
+    #[arg(long)]
+    time: bool,
+}
+
+#[derive(Debug, Args)]
+struct VerifyArgs {
+    /// Only verify this day
+    #[arg(short, long)]
+    day: Option<u32>,
+
+    /// Answers to check against, defaults to `answers.toml` in the workspace root
+    #[arg(long)]
+    answers: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+struct ValvesArgs {
+    /// Only draw the starting valve and the valves with a non-zero rate, connected by
+    /// edges labelled with the minutes it takes to walk between them
+    #[arg(long)]
+    compressed: bool,
+
+    /// Highlight the best plan for this many agents
+    #[arg(long)]
+    agents: Option<usize>,
+
+    /// Minutes the highlighted plan has, defaults to 30 for one agent and 26 otherwise
+    #[arg(long, requires = "agents")]
+    time: Option<i32>,
+
+    /// Directory inputs are cached in, see `run --cache-dir`
+    #[arg(long)]
+    cache_dir: Option<PathBuf>,
+}
+
+fn input_path(day: u32) -> PathBuf {
+    data_path(day, "input")
+}
+
+fn print_answer(day: u32, part: u32, answer: &str) {
+    if answer.contains('\n') {
+        println!("Day {day}, part {part}:\n{answer}");
+    } else {
+        println!("Day {day}, part {part}: {answer}");
+    }
+}
+
+/// Formats an error for the terminal, rendering the offending input if parsing failed.
+fn describe(e: &anyhow::Error) -> String {
+    match e
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<ParseReport>())
+    {
+        Some(report) => format!("{e:#}\n{}", report.render()),
+        None => format!("{e:#}"),
+    }
+}
+
+fn time_all() -> Result<()> {
+    let mut timings = vec![];
+    let mut failed = 0;
+
+    for day in DAYS {
+        let path = input_path(day.day);
+        match fs::read_to_string(&path) {
+            Ok(input) => {
+                let (timing, day_failed) = timing::run(day, input.as_str(), None);
+                timings.push(timing);
+                failed += day_failed;
+            }
+            Err(e) => {
+                failed += 1;
+                println!(
+                    "Day {}: error: Unable to read input from {}: {e}",
+                    day.day,
+                    path.display()
+                );
+            }
+        }
+    }
+
+    timing::print_summary(&timings);
+
+    if failed > 0 {
+        Err(anyhow!("{failed} solver(s) failed"))
+    } else {
+        Ok(())
+    }
+}
+
+fn run_all() -> Result<()> {
+    let mut failed = 0;
+
+    for solution in SOLUTIONS {
+        let path = input_path(solution.day);
+        let res = fs::read_to_string(&path)
+            .with_context(|| format!("Unable to read input from {}", path.display()))
+            .and_then(|input| (solution.solve)(input.as_str()));
+
+        match res {
+            Ok(answer) => print_answer(solution.day, solution.part, answer.as_str()),
+            Err(e) => {
+                failed += 1;
+                println!(
+                    "Day {}, part {}: error: {}",
+                    solution.day,
+                    solution.part,
+                    describe(&e)
+                );
+            }
+        }
+    }
+
+    if failed > 0 {
+        Err(anyhow!("{failed} solver(s) failed"))
+    } else {
+        Ok(())
+    }
+}
+
+fn input_cache(cache_dir: Option<PathBuf>) -> InputCache<HttpFetcher> {
+    match cache_dir {
+        Some(dir) => InputCache::new(dir, HttpFetcher::from_env()),
+        None => InputCache::from_env(HttpFetcher::from_env()),
+    }
+}
+
+/// Reads a day's input from stdin, falling back to the input cache if stdin is a
+/// terminal or empty.
+fn read_day_input(day: u32, cache: &InputCache<HttpFetcher>) -> Result<String> {
+    if io::stdin().is_terminal() {
+        return cache.get(YEAR, day);
+    }
+    let input = read_input_as_string()?;
+    if input.trim().is_empty() {
+        cache.get(YEAR, day)
+    } else {
+        Ok(input)
+    }
+}
+
+fn run_day(day: u32, part: Option<u32>, cache: &InputCache<HttpFetcher>, time: bool) -> Result<()> {
+    let solutions = SOLUTIONS
+        .iter()
+        .filter(|solution| solution.day == day && part.map_or(true, |p| p == solution.part))
+        .collect::<Vec<_>>();
+
+    if solutions.is_empty() {
+        return Err(match part {
+            Some(part) => anyhow!("No solver registered for day {day}, part {part}"),
+            None => anyhow!("No solver registered for day {day}"),
+        });
+    }
+
+    let input = read_day_input(day, cache)?;
+
+    if time {
+        let day = DAYS
+            .iter()
+            .find(|d| d.day == day)
+            .ok_or(anyhow!("No solver registered for day {day}"))?;
+        let (timing, failed) = timing::run(day, input.as_str(), part);
+        timing::print_summary(&[timing]);
+        return if failed > 0 {
+            Err(anyhow!("{failed} step(s) failed"))
+        } else {
+            Ok(())
+        };
+    }
+
+    for solution in solutions {
+        let answer = (solution.solve)(input.as_str())?;
+        if part.is_some() {
+            println!("{answer}");
+        } else {
+            print_answer(solution.day, solution.part, answer.as_str());
+        }
+    }
+
+    Ok(())
+}
+
+fn valves(args: ValvesArgs) -> Result<()> {
+    let input = read_day_input(16, &input_cache(args.cache_dir))?;
+    let graph = if args.compressed {
+        DotGraph::Compressed
+    } else {
+        DotGraph::Tunnels
+    };
+    let plan = args.agents.map(|agents| {
+        let time = args.time.unwrap_or(if agents == 1 { 30 } else { 26 });
+        (agents, time)
+    });
+
+    println!(
+        "{}",
+        get_dot(input.lines().map(|l| l.to_string()), graph, plan)?
+    );
+
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    let res = match cli.command {
+        Command::Run(RunArgs {
+            all: true,
+            time: true,
+            ..
+        }) => time_all(),
+        Command::Run(RunArgs { all: true, .. }) => run_all(),
+        Command::Run(RunArgs {
+            day,
+            part,
+            cache_dir,
+            time,
+            ..
+        }) => {
+            let cache = input_cache(cache_dir);
+            day.context("Missing day to run")
+                .and_then(|day| run_day(day, part, &cache, time))
+        }
+        Command::Verify(VerifyArgs { day, answers }) => {
+            let answers = answers.unwrap_or_else(|| {
+                [env!("CARGO_MANIFEST_DIR"), "..", "answers.toml"]
+                    .iter()
+                    .collect()
+            });
+            verify::verify(answers.as_path(), day)
+        }
+        Command::Valves(args) => valves(args),
+    };
+
+    match res {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {}", describe(&e));
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use std::{any::Any, marker::PhantomData};
+
+use anyhow::{anyhow, Context, Result};
+use util::Solver;
+
+pub type SolveFn = fn(&str) -> Result<String>;
+
+#[derive(Clone, Copy)]
+pub struct Solution {
+    pub day: u32,
+    pub part: u32,
+    pub solve: SolveFn,
+}
+
+fn solve_part1<S: Solver>(input: &str) -> Result<String> {
+    Ok(S::part1(&S::parse(input)?)?.to_string())
+}
+
+fn solve_part2<S: Solver>(input: &str) -> Result<String> {
+    Ok(S::part2(&S::parse(input)?)?.to_string())
+}
+
+/// Object safe counterpart of [`Solver`], so that days with different input types can
+/// live in one table and still have parsing and each part run (and timed) separately.
+pub trait DynSolver: Sync {
+    fn parse(&self, input: &str) -> Result<Box<dyn Any>>;
+    fn part1(&self, input: &dyn Any) -> Result<String>;
+    fn part2(&self, input: &dyn Any) -> Result<String>;
+}
+
+struct Erased<S>(PhantomData<fn() -> S>);
+
+impl<S: Solver> Erased<S>
+where
+    S::Input: 'static,
+{
+    fn input(input: &dyn Any) -> Result<&S::Input> {
+        input
+            .downcast_ref()
+            .context("Input was parsed by a different solver")
+    }
+}
+
+impl<S: Solver> DynSolver for Erased<S>
+where
+    S::Input: 'static,
+{
+    fn parse(&self, input: &str) -> Result<Box<dyn Any>> {
+        Ok(Box::new(S::parse(input)?))
+    }
+
+    fn part1(&self, input: &dyn Any) -> Result<String> {
+        Ok(S::part1(Self::input(input)?)?.to_string())
+    }
+
+    fn part2(&self, input: &dyn Any) -> Result<String> {
+        Ok(S::part2(Self::input(input)?)?.to_string())
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct Day {
+    pub day: u32,
+    pub solver: &'static dyn DynSolver,
+}
+
+impl Day {
+    pub fn part(&self, part: u32, input: &dyn Any) -> Result<String> {
+        match part {
+            1 => self.solver.part1(input),
+            2 => self.solver.part2(input),
+            _ => Err(anyhow!("Day {} has no part {part}", self.day)),
+        }
+    }
+}
+
+macro_rules! registry {
+    ($($day:literal => $solver:ty),* $(,)?) => {
+        pub static SOLUTIONS: &[Solution] = &[$(
+            Solution {
+                day: $day,
+                part: 1,
+                solve: solve_part1::<$solver>,
+            },
+            Solution {
+                day: $day,
+                part: 2,
+                solve: solve_part2::<$solver>,
+            },
+        )*];
+
+        pub static DAYS: &[Day] = &[$(
+            Day {
+                day: $day,
+                solver: &Erased::<$solver>(PhantomData),
+            },
+        )*];
+    };
+}
+
+registry![
+    1 => day1::Day1,
+    2 => day2::Day2,
+    3 => day3::Day3,
+    4 => day4::Day4,
+    5 => day5::Day5,
+    6 => day6::Day6,
+    7 => day7::Day7,
+    8 => day8::Day8,
+    9 => day9::Day9,
+    10 => day10::Day10,
+    11 => day11::Day11,
+    12 => day12::Day12,
+    13 => day13::Day13,
+    14 => day14::Day14,
+    15 => day15::Day15,
+    16 => day16::Day16,
+    17 => day17::Day17,
+    18 => day18::Day18,
+    19 => day19::Day19,
+];
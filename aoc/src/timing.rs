@@ -0,0 +1,101 @@
+use std::time::{Duration, Instant};
+
+use aoc::registry::Day;
+
+use crate::{describe, print_answer};
+
+pub struct Timing {
+    day: u32,
+    parse: Duration,
+    parts: [Option<Duration>; 2],
+}
+
+fn timed<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let res = f();
+    (res, start.elapsed())
+}
+
+/// Parses `input` once and runs the requested parts on it, printing answers (or errors)
+/// as they come. Returns the time each step took and how many of them failed.
+pub fn run(day: &Day, input: &str, part: Option<u32>) -> (Timing, u32) {
+    let (parsed, parse) = timed(|| day.solver.parse(input));
+    let mut timing = Timing {
+        day: day.day,
+        parse,
+        parts: [None, None],
+    };
+
+    let parsed = match parsed {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            println!("Day {}: parse error: {}", day.day, describe(&e));
+            return (timing, 1);
+        }
+    };
+
+    let mut failed = 0;
+
+    for p in [1, 2]
+        .into_iter()
+        .filter(|p| part.map_or(true, |part| part == *p))
+    {
+        let (answer, elapsed) = timed(|| day.part(p, parsed.as_ref()));
+        match answer {
+            Ok(answer) => {
+                print_answer(day.day, p, answer.as_str());
+                timing.parts[p as usize - 1] = Some(elapsed);
+            }
+            Err(e) => {
+                failed += 1;
+                println!("Day {}, part {p}: error: {}", day.day, describe(&e));
+            }
+        }
+    }
+
+    (timing, failed)
+}
+
+fn cell(duration: Option<Duration>) -> String {
+    match duration {
+        Some(d) => format!("{:>12}", format!("{d:.2?}")),
+        None => format!("{:>12}", "-"),
+    }
+}
+
+pub fn print_summary(timings: &[Timing]) {
+    println!();
+    println!(
+        "{:>5} |{:>12} |{:>12} |{:>12} |{:>12}",
+        "Day", "Parse", "Part 1", "Part 2", "Total"
+    );
+    println!("{}", "-".repeat(6 + 14 * 4 - 1));
+
+    let mut total = Duration::ZERO;
+
+    for timing in timings {
+        let day_total = timing.parse + timing.parts.iter().flatten().sum::<Duration>();
+        total += day_total;
+
+        println!(
+            "{:>5} |{} |{} |{} |{}",
+            timing.day,
+            cell(Some(timing.parse)),
+            cell(timing.parts[0]),
+            cell(timing.parts[1]),
+            cell(Some(day_total))
+        );
+    }
+
+    if timings.len() > 1 {
+        println!("{}", "-".repeat(6 + 14 * 4 - 1));
+        println!(
+            "{:>5} |{} |{} |{} |{}",
+            "All",
+            cell(None),
+            cell(None),
+            cell(None),
+            cell(Some(total))
+        );
+    }
+}
@@ -0,0 +1,167 @@
+use std::{fs, path::Path};
+
+use anyhow::{anyhow, Context, Result};
+use toml::Value;
+
+use aoc::{data_path, registry::SOLUTIONS};
+
+use crate::describe;
+
+/// The accepted answer for one part of a day, on the input in `dayN/data/<file>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Expected {
+    pub day: u32,
+    pub file: String,
+    pub part: u32,
+    pub answer: String,
+}
+
+fn parse_key(key: &str, prefix: &str) -> Result<u32> {
+    key.strip_prefix(prefix)
+        .and_then(|n| n.parse().ok())
+        .ok_or(anyhow!("Expected `{prefix}<number>`, found `{key}`"))
+}
+
+pub fn parse_answers(answers: &str) -> Result<Vec<Expected>> {
+    let days = answers.parse::<Value>()?;
+    let days = days.as_table().context("Answers must be a table")?;
+
+    let mut expected = vec![];
+
+    for (day_key, files) in days {
+        let day = parse_key(day_key, "day")?;
+        let files = files
+            .as_table()
+            .with_context(|| format!("`{day_key}` must be a table of input files"))?;
+
+        for (file, parts) in files {
+            let parts = parts
+                .as_table()
+                .with_context(|| format!("`{day_key}.{file}` must be a table of parts"))?;
+
+            for (part_key, answer) in parts {
+                let answer = match answer {
+                    Value::String(s) => s.clone(),
+                    Value::Integer(i) => i.to_string(),
+                    _ => {
+                        return Err(anyhow!(
+                            "`{day_key}.{file}.{part_key}` must be a string or an integer"
+                        ))
+                    }
+                };
+
+                expected.push(Expected {
+                    day,
+                    file: file.clone(),
+                    part: parse_key(part_key, "part")?,
+                    answer,
+                });
+            }
+        }
+    }
+
+    expected.sort_by_key(|e| (e.day, e.file == "input", e.file.clone(), e.part));
+
+    Ok(expected)
+}
+
+fn check(expected: &Expected) -> Result<Option<String>> {
+    let solution = SOLUTIONS
+        .iter()
+        .find(|s| s.day == expected.day && s.part == expected.part)
+        .ok_or(anyhow!("No solver registered"))?;
+
+    let path = data_path(expected.day, expected.file.as_str());
+    let input = fs::read_to_string(&path)
+        .with_context(|| format!("Unable to read input from {}", path.display()))?;
+
+    let answer = (solution.solve)(input.as_str())?;
+
+    Ok((answer != expected.answer).then_some(answer))
+}
+
+fn show(answer: &str) -> String {
+    if answer.contains('\n') {
+        format!("\n{answer}\n")
+    } else {
+        answer.to_string()
+    }
+}
+
+pub fn verify(answers: &Path, day: Option<u32>) -> Result<()> {
+    let answers = fs::read_to_string(answers)
+        .with_context(|| format!("Unable to read answers from {}", answers.display()))?;
+    let expected = parse_answers(answers.as_str())?
+        .into_iter()
+        .filter(|e| day.map_or(true, |d| d == e.day))
+        .collect::<Vec<_>>();
+
+    let mut failed = 0;
+
+    for e in expected.iter() {
+        let prefix = format!("Day {}, part {} ({})", e.day, e.part, e.file);
+        match check(e) {
+            Ok(None) => println!("{prefix}: ok"),
+            Ok(Some(answer)) => {
+                failed += 1;
+                println!(
+                    "{prefix}: MISMATCH, expected {} got {}",
+                    show(e.answer.as_str()),
+                    show(answer.as_str())
+                );
+            }
+            Err(err) => {
+                failed += 1;
+                println!("{prefix}: error: {}", describe(&err));
+            }
+        }
+    }
+
+    println!("{} answer(s) checked, {failed} failed", expected.len());
+
+    if failed > 0 {
+        Err(anyhow!("{failed} answer(s) didn't verify"))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let res = parse_answers(
+            r#"
+            [day10.test_input]
+            part1 = 13140
+            [day9.input]
+            part2 = "2619"
+            [day9.test_input]
+            part1 = 13
+            "#,
+        );
+        assert!(res.is_ok());
+        let days = res
+            .unwrap()
+            .into_iter()
+            .map(|e| (e.day, e.file, e.part, e.answer))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            days,
+            vec![
+                (9, "test_input".to_string(), 1, "13".to_string()),
+                (9, "input".to_string(), 2, "2619".to_string()),
+                (10, "test_input".to_string(), 1, "13140".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_bad_key() {
+        assert!(parse_answers("[dayX.input]\npart1 = 1").is_err());
+        assert!(parse_answers("[day1.input]\nfirst = 1").is_err());
+        assert!(parse_answers("[day1.input]\npart1 = 1.5").is_err());
+    }
+}
@@ -0,0 +1,53 @@
+use std::collections::BTreeSet;
+
+use anyhow::Result;
+use util::Solver;
+
+fn find_ordered_totals(input: impl Iterator<Item = impl Into<String>>) -> Result<BTreeSet<i32>> {
+    let mut totals = BTreeSet::new();
+    let mut current_total = 0;
+    for line in input {
+        let line: String = line.into();
+        if line.is_empty() {
+            totals.insert(current_total);
+            current_total = 0;
+        } else {
+            let calories = line.parse::<i32>()?;
+            current_total += calories;
+        }
+    }
+
+    Ok(totals)
+}
+
+fn get_biggest_n_total(totals: &BTreeSet<i32>, n: usize) -> i32 {
+    totals.iter().rev().take(n).sum()
+}
+
+pub fn get_biggest_total(input: impl Iterator<Item = impl Into<String>>) -> Result<i32> {
+    Ok(get_biggest_n_total(&find_ordered_totals(input)?, 1))
+}
+
+pub fn get_biggest_three_total(input: impl Iterator<Item = impl Into<String>>) -> Result<i32> {
+    Ok(get_biggest_n_total(&find_ordered_totals(input)?, 3))
+}
+
+pub struct Day1;
+
+impl Solver for Day1 {
+    type Input = BTreeSet<i32>;
+    type Answer1 = i32;
+    type Answer2 = i32;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        find_ordered_totals(input.lines())
+    }
+
+    fn part1(totals: &Self::Input) -> Result<Self::Answer1> {
+        Ok(get_biggest_n_total(totals, 1))
+    }
+
+    fn part2(totals: &Self::Input) -> Result<Self::Answer2> {
+        Ok(get_biggest_n_total(totals, 3))
+    }
+}
@@ -4,7 +4,7 @@
 use std::iter;
 use std::str::FromStr;
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use nom::{
     branch::alt,
     bytes::complete::tag,
@@ -13,6 +13,7 @@ use nom::{
     sequence::{preceded, tuple},
     Finish, IResult,
 };
+use util::Solver;
 
 #[derive(Debug, Copy, Clone)]
 pub enum Instruction {
@@ -50,13 +51,26 @@ pub fn get_instructions(input: impl Iterator<Item = String>) -> impl Iterator<It
     })
 }
 
-pub fn get_sum_signal_strengths(
-    input: impl Iterator<Item = String>,
+fn parse_instructions(input: impl Iterator<Item = String>) -> Result<Vec<Instruction>> {
+    input
+        .map(|l| {
+            all_consuming(parse_instruction)(l.as_str())
+                .finish()
+                .map(|(_, instruction)| instruction)
+                .map_err(|e| anyhow!("Unable to parse instruction: {e}"))
+        })
+        .collect()
+}
+
+fn sum_signal_strengths(
+    instructions: &[Instruction],
     first_cycle: usize,
     cycle_size: usize,
     num_signals: usize,
-) -> Result<i32> {
-    let sum: i32 = get_instructions(input)
+) -> i32 {
+    instructions
+        .iter()
+        .copied()
         .flat_map(|i| match i {
             Instruction::Noop => vec![None],
             Instruction::Addx(n) => vec![None, Some(n)],
@@ -73,14 +87,28 @@ pub fn get_sum_signal_strengths(
         .step_by(cycle_size)
         .take(num_signals)
         .map(|(i, x)| i as i32 * x)
-        .sum();
+        .sum()
+}
+
+pub fn get_sum_signal_strengths(
+    input: impl Iterator<Item = String>,
+    first_cycle: usize,
+    cycle_size: usize,
+    num_signals: usize,
+) -> Result<i32> {
+    let instructions = get_instructions(input).collect::<Vec<_>>();
 
-    Ok(sum)
+    Ok(sum_signal_strengths(
+        &instructions,
+        first_cycle,
+        cycle_size,
+        num_signals,
+    ))
 }
 
-pub fn get_crt_output(input: impl Iterator<Item = String>, rows: usize, cols: usize) -> String {
+fn crt_output(instructions: &[Instruction], rows: usize, cols: usize) -> String {
     iter::once(None)
-        .chain(get_instructions(input).flat_map(|i| match i {
+        .chain(instructions.iter().copied().flat_map(|i| match i {
             Instruction::Noop => vec![None],
             Instruction::Addx(n) => vec![None, Some(n)],
         }))
@@ -110,11 +138,35 @@ pub fn get_crt_output(input: impl Iterator<Item = String>, rows: usize, cols: us
         .collect()
 }
 
+pub fn get_crt_output(input: impl Iterator<Item = String>, rows: usize, cols: usize) -> String {
+    crt_output(&get_instructions(input).collect::<Vec<_>>(), rows, cols)
+}
+
 // Added after checking answers on reddit :)
 pub fn prettify(output: String) -> String {
     output.replace("#", "██").replace(".", "░░")
 }
 
+pub struct Day10;
+
+impl Solver for Day10 {
+    type Input = Vec<Instruction>;
+    type Answer1 = i32;
+    type Answer2 = String;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_instructions(input.lines().map(|l| l.to_string()))
+    }
+
+    fn part1(instructions: &Self::Input) -> Result<Self::Answer1> {
+        Ok(sum_signal_strengths(instructions, 20, 40, 6))
+    }
+
+    fn part2(instructions: &Self::Input) -> Result<Self::Answer2> {
+        Ok(prettify(crt_output(instructions, 6, 40)))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,6 +1,6 @@
 #![feature(iter_intersperse)]
 
-use day11::get_monkey_business;
+use day11::{get_monkey_business, Config};
 
 use std::io::{self, BufRead};
 
@@ -17,8 +17,7 @@ fn main() -> Result<()> {
             .collect::<std::result::Result<String, _>>()
             .map_err(anyhow::Error::msg)?
             .as_str(),
-        10000,
-        false,
+        &Config::PART2,
     );
 
     println!("{}", res.unwrap());
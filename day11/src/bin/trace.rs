@@ -0,0 +1,51 @@
+#![feature(iter_intersperse)]
+
+use day11::{get_trace, Config};
+
+use std::{
+    env,
+    io::{self, BufRead},
+};
+
+use anyhow::{anyhow, Context, Result};
+
+fn main() -> Result<()> {
+    let mut args = env::args().skip(1);
+    let format = args.next().unwrap_or_else(|| "json".to_string());
+    let mut config = match args.next().as_deref() {
+        None | Some("1") => Config::PART1,
+        Some("2") => Config::PART2,
+        Some(part) => return Err(anyhow!("Unknown part: {part}")),
+    };
+    if let Some(rounds) = args.next() {
+        config.rounds = rounds
+            .parse()
+            .context("Number of rounds must be a number")?;
+    }
+
+    let stdin = io::stdin();
+
+    let trace = get_trace(
+        stdin
+            .lock()
+            .lines()
+            .intersperse_with(|| Ok("\n".to_string()))
+            .collect::<std::result::Result<String, _>>()
+            .map_err(anyhow::Error::msg)?
+            .as_str(),
+        &config,
+    )?;
+
+    match format.as_str() {
+        "json" => println!("{}", trace.to_json()?),
+        "monkeys" => print!("{}", trace.monkeys_csv()),
+        "throws" => print!("{}", trace.throws_csv()),
+        _ => {
+            return Err(anyhow!(
+                "Unknown format {format}, expected json, monkeys or throws"
+            ))
+        }
+    }
+
+    Ok(())
+}
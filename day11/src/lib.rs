@@ -1,245 +1,443 @@
-use std::{
-    collections::HashMap,
-    ops::{Add, Mul},
-    str::FromStr,
-};
+use std::{collections::HashMap, fmt, hash::Hash, str::FromStr};
 
-use anyhow::{anyhow, Context, Result};
+use anyhow::{anyhow, Result};
+use itertools::Itertools;
 use nom::{
     branch::alt,
     bytes::complete::tag,
     character::{
         complete::alpha1,
-        complete::{char, multispace0},
+        complete::{char, multispace0, one_of, space0},
         complete::{digit1, line_ending},
     },
-    combinator::{all_consuming, map, map_res, verify},
-    multi::{many0, separated_list0},
-    sequence::{delimited, preceded, terminated, tuple},
-    Finish, IResult,
-};
-use num::{
-    integer::{div_floor, div_rem},
-    Integer,
+    combinator::{cut, map, map_res, value, verify},
+    error::{FromExternalError, ParseError},
+    multi::{fold_many0, many0, separated_list0},
+    sequence::{delimited, pair, preceded, tuple},
+    IResult,
 };
+use num::{BigUint, CheckedSub, Integer, Zero};
+use serde::Serialize;
+use thiserror::Error;
+use util::{parse_input, Solver, Span};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
 
-pub enum Item {
-    Number(i32),
-    PrimeRemindersOfNumber(HashMap<i32, i32>),
-}
-
-impl Mul for Item {
-    type Output = Item;
-
-    fn mul(self, rhs: Item) -> Self::Output {
-        match (&self, &rhs) {
-            (
-                &Item::PrimeRemindersOfNumber(ref rem_map_self),
-                &Item::PrimeRemindersOfNumber(ref rem_map_rhs),
-            ) => Item::PrimeRemindersOfNumber(
-                rem_map_self
-                    .into_iter()
-                    .map(|(divisor, rem_self)| {
-                        let rem_rhs = rem_map_rhs.get(&divisor).unwrap();
-                        (*divisor, div_rem(*rem_self * *rem_rhs, *divisor).1)
-                    })
-                    .collect(),
-            ),
-            (_, &Item::Number(i)) => self * i,
-            (&Item::Number(i), _) => rhs * i,
-        }
+impl fmt::Display for BinOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let op = match self {
+            BinOp::Add => '+',
+            BinOp::Sub => '-',
+            BinOp::Mul => '*',
+            BinOp::Div => '/',
+            BinOp::Rem => '%',
+        };
+        write!(f, "{op}")
     }
 }
 
-impl Mul<i32> for Item {
-    type Output = Item;
+/// Worry levels an [`Expr`] can be evaluated on. Remainders are never negative.
+pub trait Value: Clone + Sized {
+    /// `i` in the same representation as `self`.
+    fn constant(&self, i: u32) -> Result<Self>;
+    fn apply(self, op: BinOp, rhs: Self) -> Result<Self>;
 
-    fn mul(self, rhs: i32) -> Self::Output {
-        match self {
-            Item::Number(i) => Item::Number(i * rhs),
-            Item::PrimeRemindersOfNumber(rem_map) => Item::PrimeRemindersOfNumber(
-                rem_map
-                    .into_iter()
-                    .map(|(divisor, rem)| (divisor, div_rem(rem * rhs, divisor).1))
-                    .collect(),
-            ),
-        }
+    /// `self % k` for a literal `k`, which may not survive [`Value::constant`] as is.
+    fn rem_constant(self, k: u32) -> Result<Self> {
+        let rhs = self.constant(k)?;
+        self.apply(BinOp::Rem, rhs)
     }
 }
 
-impl Add<i32> for Item {
-    type Output = Item;
+macro_rules! impl_value {
+    ($($t:ty),*) => {$(
+        impl Value for $t {
+            fn constant(&self, i: u32) -> Result<Self> {
+                Ok(i.try_into()?)
+            }
 
-    fn add(self, rhs: i32) -> Self::Output {
-        match self {
-            Item::Number(i) => Item::Number(i + rhs),
-            Item::PrimeRemindersOfNumber(rem_map) => Item::PrimeRemindersOfNumber(
-                rem_map
-                    .into_iter()
-                    .map(|(divisor, rem)| (divisor, div_rem(rem + rhs, divisor).1))
-                    .collect(),
-            ),
+            fn apply(self, op: BinOp, rhs: Self) -> Result<Self> {
+                match op {
+                    BinOp::Add => self.checked_add(rhs),
+                    BinOp::Sub => self.checked_sub(rhs),
+                    BinOp::Mul => self.checked_mul(rhs),
+                    BinOp::Div => self.checked_div(rhs),
+                    BinOp::Rem => self.checked_rem_euclid(rhs),
+                }
+                .ok_or_else(|| anyhow!("Evaluating {self} {op} {rhs} overflowed or divided by 0"))
+            }
         }
-    }
+    )*};
 }
 
-impl Item {
-    fn to_prime_remainders(self, primes: &[i32]) -> Self {
-        match self {
-            Item::Number(i) => Item::PrimeRemindersOfNumber(
-                primes
-                    .iter()
-                    .map(|prime| (*prime, i.div_rem(prime).1))
-                    .collect(),
-            ),
-            Item::PrimeRemindersOfNumber(_) => self,
+impl_value!(i32, i64);
+
+impl Value for BigUint {
+    fn constant(&self, i: u32) -> Result<Self> {
+        Ok(i.into())
+    }
+
+    fn apply(self, op: BinOp, rhs: Self) -> Result<Self> {
+        if matches!(op, BinOp::Div | BinOp::Rem) && rhs.is_zero() {
+            return Err(anyhow!("Evaluating {self} {op} {rhs} divided by 0"));
+        }
+        match op {
+            BinOp::Add => Ok(self + rhs),
+            BinOp::Sub => self
+                .checked_sub(&rhs)
+                .ok_or_else(|| anyhow!("Evaluating {self} {op} {rhs} went below 0")),
+            BinOp::Mul => Ok(self * rhs),
+            BinOp::Div => Ok(self / rhs),
+            BinOp::Rem => Ok(self % rhs),
         }
     }
+}
 
-    fn div_floor(&self, rhs: i32) -> Self {
-        match self {
-            Item::Number(i) => Item::Number(div_floor(*i, rhs)),
-            Item::PrimeRemindersOfNumber(_) => unimplemented!(),
+/// A worry level modulo the least common multiple of the test divisors. That's enough
+/// to decide every test, but the level itself is lost, so it can't be divided.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Modular {
+    value: u64,
+    modulus: u64,
+}
+
+impl Modular {
+    pub fn new(value: u64, modulus: u64) -> Self {
+        Modular {
+            value: value % modulus,
+            modulus,
         }
     }
+}
 
-    fn is_multiple_of(&self, divisor: i32) -> bool {
-        match self {
-            Item::Number(i) => i.is_multiple_of(&divisor),
-            Item::PrimeRemindersOfNumber(rem_map) => {
-                rem_map.get(&divisor).expect(
-                    format!("Map of prime reminders wasn't initialized for {divisor}").as_str(),
-                ) == &0
-            }
+impl fmt::Display for Modular {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+impl Value for Modular {
+    fn constant(&self, i: u32) -> Result<Self> {
+        Ok(Modular::new(i.into(), self.modulus))
+    }
+
+    fn apply(self, op: BinOp, rhs: Self) -> Result<Self> {
+        let m = self.modulus as u128;
+        let (l, r) = (self.value as u128, rhs.value as u128);
+        let value = match op {
+            BinOp::Add => (l + r) % m,
+            BinOp::Sub => (l + m - r) % m,
+            BinOp::Mul => l * r % m,
+            // Remainders by a divisor of the modulus don't depend on the lost multiples
+            BinOp::Rem if r != 0 && m % r == 0 => l % r,
+            BinOp::Rem => return Err(anyhow!("Can't take remainders by {r} modulo {m}")),
+            BinOp::Div => return Err(anyhow!("Can't divide worry levels modulo {m}")),
+        };
+        Ok(Modular::new(value as u64, self.modulus))
+    }
+
+    fn rem_constant(self, k: u32) -> Result<Self> {
+        let (l, k) = (self.value, k as u64);
+        if k == self.modulus {
+            // The level itself is below the modulus
+            Ok(self)
+        } else if k != 0 && self.modulus % k == 0 {
+            Ok(Modular::new(l % k, self.modulus))
+        } else {
+            Err(anyhow!(
+                "Can't take remainders by {k} modulo {}",
+                self.modulus
+            ))
         }
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-pub enum OpRhs {
-    Int(i32),
+/// Worry levels the monkeys can pass around.
+trait WorryLevel: Value + fmt::Display {
+    fn is_multiple_of(&self, divisor: u32) -> bool;
+    fn relieved(self, by: u32) -> Result<Self>;
+}
+
+impl WorryLevel for BigUint {
+    fn is_multiple_of(&self, divisor: u32) -> bool {
+        Integer::is_multiple_of(self, &divisor.into())
+    }
+
+    fn relieved(self, by: u32) -> Result<Self> {
+        self.apply(BinOp::Div, by.into())
+    }
+}
+
+impl WorryLevel for Modular {
+    fn is_multiple_of(&self, divisor: u32) -> bool {
+        self.value % divisor as u64 == 0
+    }
+
+    fn relieved(self, _: u32) -> Result<Self> {
+        Err(anyhow!("Can't divide worry levels modulo {}", self.modulus))
+    }
+}
+
+/// Right hand side of a monkey's `Operation: new = ...` line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
     Old,
+    Int(u32),
+    BinOp(Box<Expr>, BinOp, Box<Expr>),
 }
 
-#[derive(Debug, Clone, Copy)]
-pub enum Operation {
-    Multiply(OpRhs),
-    Add(OpRhs),
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Old => write!(f, "old"),
+            Expr::Int(i) => write!(f, "{i}"),
+            Expr::BinOp(lhs, op, rhs) => write!(f, "({lhs} {op} {rhs})"),
+        }
+    }
+}
+
+impl Expr {
+    pub fn eval<V: Value>(&self, old: &V) -> Result<V> {
+        match self {
+            Expr::Old => Ok(old.clone()),
+            Expr::Int(i) => old.constant(*i),
+            Expr::BinOp(lhs, op, rhs) => {
+                let lhs = lhs.eval(old)?;
+                match (op, rhs.as_ref()) {
+                    (BinOp::Rem, Expr::Int(k)) => lhs.rem_constant(*k),
+                    _ => lhs.apply(*op, rhs.eval(old)?),
+                }
+            }
+        }
+    }
 }
 
+/// The input lines are kept around to point at them when validation fails.
 #[derive(Debug, Clone, Copy)]
 pub struct Test {
-    divisible_by: i32,
+    divisible_by: u32,
+    divisible_by_line: u32,
     true_pass_to: usize,
+    true_line: u32,
     false_pass_to: usize,
+    false_line: u32,
 }
 
 #[derive(Debug, Clone)]
 pub struct Monkey {
-    items: Vec<Item>,
-    operation: Operation,
+    line: u32,
+    items: Vec<u64>,
+    operation: Expr,
     test: Test,
 }
 
-fn parse_pass_to(condition_match_value: bool, i: &str) -> IResult<&str, usize> {
+/// Monkey definitions that parse fine but can't be simulated.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum MonkeyError {
+    #[error("line {line}: expected Monkey {expected}, found Monkey {found}")]
+    OutOfOrder {
+        line: u32,
+        expected: usize,
+        found: usize,
+    },
+
+    #[error("line {line}: Monkey {monkey} throws to nonexisting Monkey {target}")]
+    MissingTarget {
+        line: u32,
+        monkey: usize,
+        target: usize,
+    },
+
+    #[error("line {line}: Monkey {monkey} throws to itself")]
+    SelfThrow { line: u32, monkey: usize },
+
+    #[error("line {line}: Monkey {monkey} tests divisibility by 0")]
+    ZeroDivisor { line: u32, monkey: usize },
+}
+
+/// 1-based line the input is at, without consuming anything.
+fn line_number<'a, E>(i: Span<'a>) -> IResult<Span<'a>, u32, E>
+where
+    E: ParseError<Span<'a>>,
+{
+    Ok((i, i.location_line()))
+}
+
+fn parse_from_str<'a, T, E>(i: Span<'a>) -> IResult<Span<'a>, T, E>
+where
+    T: FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+    E: ParseError<Span<'a>> + FromExternalError<Span<'a>, anyhow::Error>,
+{
+    map_res(digit1, |s: Span<'a>| {
+        s.fragment().parse().map_err(anyhow::Error::from)
+    })(i)
+}
+
+fn parse_pass_to<'a, E>(
+    condition_match_value: bool,
+    i: Span<'a>,
+) -> IResult<Span<'a>, (u32, usize), E>
+where
+    E: ParseError<Span<'a>> + FromExternalError<Span<'a>, anyhow::Error>,
+{
     preceded(
         multispace0,
-        preceded(
-            verify(
-                map_res(preceded(tag("If "), alpha1), <bool as FromStr>::from_str),
-                |s: &bool| s == &condition_match_value,
-            ),
-            map_res(
-                preceded(tag(": throw to monkey "), digit1),
-                FromStr::from_str,
+        pair(
+            line_number,
+            preceded(
+                verify(
+                    map_res(preceded(tag("If "), alpha1), |s: Span<'a>| {
+                        s.fragment().parse::<bool>().map_err(anyhow::Error::from)
+                    }),
+                    |s: &bool| s == &condition_match_value,
+                ),
+                preceded(tag(": throw to monkey "), parse_from_str),
             ),
         ),
     )(i)
 }
 
-fn parse_test(i: &str) -> IResult<&str, Test> {
+fn parse_test<'a, E>(i: Span<'a>) -> IResult<Span<'a>, Test, E>
+where
+    E: ParseError<Span<'a>> + FromExternalError<Span<'a>, anyhow::Error>,
+{
     preceded(
         multispace0,
         map(
             tuple((
-                map_res(
-                    preceded(tag("Test: divisible by "), digit1),
-                    FromStr::from_str,
+                pair(
+                    line_number,
+                    preceded(tag("Test: divisible by "), parse_from_str),
                 ),
                 |j| parse_pass_to(true, j),
                 |j| parse_pass_to(false, j),
             )),
-            |(divisible_by, true_pass_to, false_pass_to)| Test {
+            |(
+                (divisible_by_line, divisible_by),
+                (true_line, true_pass_to),
+                (false_line, false_pass_to),
+            )| Test {
                 divisible_by,
+                divisible_by_line,
                 true_pass_to,
+                true_line,
                 false_pass_to,
+                false_line,
             },
         ),
     )(i)
 }
 
-fn parse_op_rhs(i: &str) -> IResult<&str, OpRhs> {
+fn parse_factor<'a, E>(i: Span<'a>) -> IResult<Span<'a>, Expr, E>
+where
+    E: ParseError<Span<'a>> + FromExternalError<Span<'a>, anyhow::Error>,
+{
     preceded(
-        multispace0,
+        space0,
         alt((
-            map(map_res(digit1, FromStr::from_str), OpRhs::Int),
-            map_res(alpha1, |s: &str| {
-                if s == "old" {
-                    Ok(OpRhs::Old)
-                } else {
-                    Err(anyhow!("Unrecognizable operation right hand size: {s}"))
-                }
-            }),
+            map(parse_from_str, Expr::Int),
+            value(Expr::Old, tag("old")),
+            delimited(char('('), parse_expr, preceded(space0, char(')'))),
         )),
     )(i)
 }
 
-fn parse_operation(i: &str) -> IResult<&str, Operation> {
+fn parse_bin_op<'a, E>(ops: &'static str) -> impl FnMut(Span<'a>) -> IResult<Span<'a>, BinOp, E>
+where
+    E: ParseError<Span<'a>> + FromExternalError<Span<'a>, anyhow::Error>,
+{
     preceded(
-        multispace0,
-        map_res(
-            tuple((
-                preceded(tag("Operation: new = old "), alt((char('*'), char('+')))),
-                parse_op_rhs,
-            )),
-            |(op, num)| match op {
-                '*' => Ok(Operation::Multiply(num)),
-                '+' => Ok(Operation::Add(num)),
-                _ => Err(anyhow!("Unrecognized binary operator: {op}")),
-            },
-        ),
+        space0,
+        map(one_of(ops), |op| match op {
+            '+' => BinOp::Add,
+            '-' => BinOp::Sub,
+            '*' => BinOp::Mul,
+            '/' => BinOp::Div,
+            _ => BinOp::Rem,
+        }),
+    )
+}
+
+fn parse_bin_ops<'a, E>(
+    ops: &'static str,
+    operand: fn(Span<'a>) -> IResult<Span<'a>, Expr, E>,
+) -> impl FnMut(Span<'a>) -> IResult<Span<'a>, Expr, E>
+where
+    E: ParseError<Span<'a>> + FromExternalError<Span<'a>, anyhow::Error>,
+{
+    move |i| {
+        let (i, first) = operand(i)?;
+        fold_many0(
+            pair(parse_bin_op(ops), operand),
+            move || first.clone(),
+            |lhs, (op, rhs)| Expr::BinOp(Box::new(lhs), op, Box::new(rhs)),
+        )(i)
+    }
+}
+
+fn parse_term<'a, E>(i: Span<'a>) -> IResult<Span<'a>, Expr, E>
+where
+    E: ParseError<Span<'a>> + FromExternalError<Span<'a>, anyhow::Error>,
+{
+    parse_bin_ops("*/%", parse_factor)(i)
+}
+
+fn parse_expr<'a, E>(i: Span<'a>) -> IResult<Span<'a>, Expr, E>
+where
+    E: ParseError<Span<'a>> + FromExternalError<Span<'a>, anyhow::Error>,
+{
+    parse_bin_ops("+-", parse_term)(i)
+}
+
+fn parse_operation<'a, E>(i: Span<'a>) -> IResult<Span<'a>, Expr, E>
+where
+    E: ParseError<Span<'a>> + FromExternalError<Span<'a>, anyhow::Error>,
+{
+    delimited(
+        pair(multispace0, tag("Operation: new =")),
+        parse_expr,
+        pair(space0, line_ending),
     )(i)
 }
 
-fn parse_starting_items(i: &str) -> IResult<&str, Vec<Item>> {
+fn parse_starting_items<'a, E>(i: Span<'a>) -> IResult<Span<'a>, Vec<u64>, E>
+where
+    E: ParseError<Span<'a>> + FromExternalError<Span<'a>, anyhow::Error>,
+{
     delimited(
         multispace0,
         preceded(
             tag("Starting items: "),
-            separated_list0(
-                tag(", "),
-                map(map_res(digit1, FromStr::from_str), |i| Item::Number(i)),
-            ),
+            separated_list0(tag(", "), parse_from_str),
         ),
         line_ending,
     )(i)
 }
 
-fn parse_monkey(i: &str) -> IResult<&str, (Monkey, usize)> {
+fn parse_monkey<'a, E>(i: Span<'a>) -> IResult<Span<'a>, (Monkey, usize), E>
+where
+    E: ParseError<Span<'a>> + FromExternalError<Span<'a>, anyhow::Error>,
+{
     preceded(
         multispace0,
         map(
             tuple((
-                delimited(tag("Monkey "), map_res(digit1, FromStr::from_str), tag(":")),
-                parse_starting_items,
-                parse_operation,
-                parse_test,
+                line_number,
+                delimited(tag("Monkey "), parse_from_str, tag(":")),
+                cut(tuple((parse_starting_items, parse_operation, parse_test))),
             )),
-            |(i, items, operation, test)| {
+            |(line, i, (items, operation, test))| {
                 (
                     Monkey {
+                        line,
                         items,
                         operation,
                         test,
@@ -251,98 +449,412 @@ fn parse_monkey(i: &str) -> IResult<&str, (Monkey, usize)> {
     )(i)
 }
 
-pub fn get_monkey_business(input: &str, rounds: usize, manageable: bool) -> Result<i64> {
-    let monkeys = all_consuming(terminated(many0(parse_monkey), multispace0))(input)
-        .finish()
-        .unwrap()
-        .1;
-
-    let monkeys = monkeys
-        .into_iter()
-        .enumerate()
-        .map(|(i_enumerate, (monkey, i_parse))| {
-            if i_enumerate == i_parse {
-                Ok(monkey)
-            } else {
-                Err(anyhow!("Monkeys are not listed in order!"))
+/// Checks everything the simulation relies on, so it never has to fail halfway through.
+fn validate(monkeys: &[(Monkey, usize)]) -> Result<(), MonkeyError> {
+    for (expected, (monkey, found)) in monkeys.iter().enumerate() {
+        if *found != expected {
+            return Err(MonkeyError::OutOfOrder {
+                line: monkey.line,
+                expected,
+                found: *found,
+            });
+        }
+
+        let test = &monkey.test;
+        if test.divisible_by == 0 {
+            return Err(MonkeyError::ZeroDivisor {
+                line: test.divisible_by_line,
+                monkey: expected,
+            });
+        }
+        for (line, target) in [
+            (test.true_line, test.true_pass_to),
+            (test.false_line, test.false_pass_to),
+        ] {
+            if target >= monkeys.len() {
+                return Err(MonkeyError::MissingTarget {
+                    line,
+                    monkey: expected,
+                    target,
+                });
             }
-        })
-        .collect::<Result<Vec<_>>>()?;
+            if target == expected {
+                return Err(MonkeyError::SelfThrow {
+                    line,
+                    monkey: expected,
+                });
+            }
+        }
+    }
+    Ok(())
+}
 
-    let mut times_inspected_per_monkey = vec![0; monkeys.len()];
+fn parse_monkeys(input: &str) -> Result<Vec<Monkey>> {
+    let monkeys = parse_input(input, many0(parse_monkey))?;
+    validate(&monkeys)?;
 
-    let mut monkeys = if manageable {
-        monkeys
-    } else {
-        let primes = monkeys
-            .iter()
-            .map(|monkey| monkey.test.divisible_by)
-            .collect::<Vec<_>>();
+    Ok(monkeys.into_iter().map(|(monkey, _)| monkey).collect())
+}
 
-        monkeys
-            .into_iter()
-            .map(
-                |Monkey {
-                     items,
-                     operation,
-                     test,
-                 }| Monkey {
-                    items: items
-                        .into_iter()
-                        .map(|item| item.to_prime_remainders(primes.as_ref()))
-                        .collect(),
-                    operation,
-                    test,
-                },
-            )
-            .collect::<Vec<_>>()
+/// How worry levels are kept track of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Worry {
+    /// Exact worry levels, however large they get.
+    Exact,
+    /// Worry levels modulo the least common multiple of the test divisors, see
+    /// [`Modular`]. Doesn't work together with relief. Subtractions wrap around
+    /// instead of failing when the level would go below 0, since that can't be told
+    /// from the remainder alone.
+    Modular,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Config {
+    pub rounds: usize,
+    /// Worry levels are divided by this after every inspection, if at all.
+    pub relief: Option<u32>,
+    pub worry: Worry,
+}
+
+impl Config {
+    pub const PART1: Config = Config {
+        rounds: 20,
+        relief: Some(3),
+        worry: Worry::Exact,
     };
 
-    for _ in 0..rounds {
-        for i in 0..monkeys.len() {
-            let mut current_monkey = monkeys.get(i).unwrap().clone();
+    pub const PART2: Config = Config {
+        rounds: 10000,
+        relief: None,
+        worry: Worry::Modular,
+    };
+}
 
-            let current_inspected = times_inspected_per_monkey.get_mut(i).unwrap();
+/// An item passed from one monkey to another.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Throw {
+    /// Items are numbered in the order they're listed in the input, starting at 0.
+    pub item: usize,
+    pub from: usize,
+    pub to: usize,
+    /// Worry level the item arrives with.
+    pub worry: String,
+}
 
-            while let Some(worry_lvl) = current_monkey.items.pop() {
-                let new_worry_lvl = match current_monkey.operation {
-                    Operation::Multiply(x) => match x {
-                        OpRhs::Int(y) => worry_lvl * y,
-                        OpRhs::Old => worry_lvl.clone() * worry_lvl,
-                    },
-                    Operation::Add(x) => match x {
-                        OpRhs::Int(y) => worry_lvl + y,
-                        OpRhs::Old => worry_lvl * 2,
-                    },
-                };
-                let new_worry_lvl = if manageable {
-                    new_worry_lvl.div_floor(3)
-                } else {
-                    new_worry_lvl
-                };
-
-                let throw_to = if new_worry_lvl.is_multiple_of(current_monkey.test.divisible_by) {
-                    current_monkey.test.true_pass_to
-                } else {
-                    current_monkey.test.false_pass_to
-                };
-
-                monkeys
-                    .get_mut(throw_to)
-                    .context(format!("Trying to throw to nonexisting Monkey {i}"))?
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HeldItem {
+    pub item: usize,
+    pub worry: String,
+}
+
+/// State of the monkeys at the end of a round.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Round {
+    /// Starting at 1.
+    pub round: usize,
+    /// Items each monkey holds, in the order it's going to inspect them.
+    pub held: Vec<Vec<HeldItem>>,
+    /// Items each monkey inspected so far, this round included.
+    pub inspections: Vec<u64>,
+    /// Throws in the order they happened.
+    pub throws: Vec<Throw>,
+}
+
+/// Rounds of the monkeys throwing items around.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Trace {
+    pub rounds: Vec<Round>,
+}
+
+impl Trace {
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// A line per monkey per round, with the items it holds separated by spaces.
+    pub fn monkeys_csv(&self) -> String {
+        let mut csv = "round,monkey,inspections,items\n".to_string();
+        for round in self.rounds.iter() {
+            for (monkey, (held, inspections)) in
+                round.held.iter().zip(round.inspections.iter()).enumerate()
+            {
+                let items = held.iter().map(|held| held.item).join(" ");
+                csv.push_str(&format!("{},{monkey},{inspections},{items}\n", round.round));
+            }
+        }
+        csv
+    }
+
+    /// A line per throw.
+    pub fn throws_csv(&self) -> String {
+        let mut csv = "round,item,from,to,worry\n".to_string();
+        for round in self.rounds.iter() {
+            for throw in round.throws.iter() {
+                csv.push_str(&format!(
+                    "{},{},{},{},{}\n",
+                    round.round, throw.item, throw.from, throw.to, throw.worry
+                ));
+            }
+        }
+        csv
+    }
+}
+
+impl Monkey {
+    /// Monkey this monkey throws an item to after inspecting it, and its new worry level.
+    fn inspect<W: WorryLevel>(&self, worry_lvl: &W, relief: Option<u32>) -> Result<(usize, W)> {
+        let new_worry_lvl = self.operation.eval(worry_lvl)?;
+        let new_worry_lvl = match relief {
+            Some(by) => new_worry_lvl.relieved(by)?,
+            None => new_worry_lvl,
+        };
+
+        let throw_to = if new_worry_lvl.is_multiple_of(self.test.divisible_by) {
+            self.test.true_pass_to
+        } else {
+            self.test.false_pass_to
+        };
+
+        Ok((throw_to, new_worry_lvl))
+    }
+}
+
+/// Number of times each monkey inspects an item starting out with `monkey` over
+/// `rounds` rounds. Items never affect each other, so every item can be followed on its
+/// own, and once it's back at a monkey with a worry level it had at the start of an
+/// earlier round, it keeps going around the same way. The rest is extrapolated.
+fn item_inspections<W: WorryLevel + Eq + Hash>(
+    monkeys: &[Monkey],
+    relief: Option<u32>,
+    monkey: usize,
+    worry_lvl: W,
+    rounds: usize,
+) -> Result<Vec<u64>> {
+    let mut seen = HashMap::new();
+    // totals[r]: inspections by each monkey in the first r rounds
+    let mut totals = vec![vec![0; monkeys.len()]];
+    let mut state = (monkey, worry_lvl);
+
+    for round in 0..rounds {
+        if let Some(start) = seen.insert(state.clone(), round) {
+            let length = round - start;
+            let (cycles, rest) = ((rounds - round) / length, (rounds - round) % length);
+            return Ok((0..monkeys.len())
+                .map(|m| {
+                    let gain = totals[round][m] - totals[start][m];
+                    totals[round][m]
+                        + cycles as u64 * gain
+                        + (totals[start + rest][m] - totals[start][m])
+                })
+                .collect());
+        }
+
+        // Monkeys take turns in order, so an item thrown to a later monkey gets
+        // inspected again in the same round
+        let mut counts = totals[round].clone();
+        let (mut monkey, mut worry_lvl) = state;
+        loop {
+            counts[monkey] += 1;
+            let (throw_to, new_worry_lvl) = monkeys[monkey].inspect(&worry_lvl, relief)?;
+
+            let next_round = throw_to <= monkey;
+            monkey = throw_to;
+            worry_lvl = new_worry_lvl;
+            if next_round {
+                break;
+            }
+        }
+        totals.push(counts);
+        state = (monkey, worry_lvl);
+    }
+
+    Ok(totals.pop().unwrap())
+}
+
+/// Monkeys and the items they hold, numbered like in [`Throw::item`].
+struct Troop<'a, W> {
+    monkeys: &'a [Monkey],
+    relief: Option<u32>,
+    items: Vec<Vec<(usize, W)>>,
+    inspections: Vec<u64>,
+}
+
+impl<'a, W: WorryLevel> Troop<'a, W> {
+    fn new(monkeys: &'a [Monkey], relief: Option<u32>, worry_level: impl Fn(u64) -> W) -> Self {
+        let mut ids = 0..;
+        let items = monkeys
+            .iter()
+            .map(|monkey| {
+                monkey
                     .items
-                    .push(new_worry_lvl);
+                    .iter()
+                    .map(|&i| (ids.next().unwrap(), worry_level(i)))
+                    .collect()
+            })
+            .collect();
 
-                *current_inspected += 1;
+        Troop {
+            monkeys,
+            relief,
+            items,
+            inspections: vec![0; monkeys.len()],
+        }
+    }
+
+    /// Plays a round, calling `on_throw` with every item thrown.
+    fn round(&mut self, mut on_throw: impl FnMut(usize, usize, usize, &W)) -> Result<()> {
+        for (i, monkey) in self.monkeys.iter().enumerate() {
+            let current_items = std::mem::take(&mut self.items[i]);
+            self.inspections[i] += current_items.len() as u64;
+
+            for (id, worry_lvl) in current_items {
+                let (throw_to, new_worry_lvl) = monkey.inspect(&worry_lvl, self.relief)?;
+
+                on_throw(id, i, throw_to, &new_worry_lvl);
+                self.items[throw_to].push((id, new_worry_lvl));
             }
+        }
+
+        Ok(())
+    }
 
-            *monkeys.get_mut(i).unwrap() = current_monkey;
+    fn traced_round(&mut self, round: usize) -> Result<Round> {
+        let mut throws = vec![];
+        self.round(|item, from, to, worry| {
+            throws.push(Throw {
+                item,
+                from,
+                to,
+                worry: worry.to_string(),
+            })
+        })?;
+
+        Ok(Round {
+            round,
+            held: self
+                .items
+                .iter()
+                .map(|items| {
+                    items
+                        .iter()
+                        .map(|(item, worry)| HeldItem {
+                            item: *item,
+                            worry: worry.to_string(),
+                        })
+                        .collect()
+                })
+                .collect(),
+            inspections: self.inspections.clone(),
+            throws,
+        })
+    }
+}
+
+/// Number of items each monkey inspects over `rounds` rounds.
+fn inspections<W: WorryLevel + Eq + Hash>(
+    monkeys: &[Monkey],
+    config: &Config,
+    worry_level: impl Fn(u64) -> W,
+) -> Result<Vec<u64>> {
+    let mut times_inspected_per_monkey = vec![0; monkeys.len()];
+    for (i, monkey) in monkeys.iter().enumerate() {
+        for &item in monkey.items.iter() {
+            let item_inspections =
+                item_inspections(monkeys, config.relief, i, worry_level(item), config.rounds)?;
+            for (total, n) in times_inspected_per_monkey.iter_mut().zip(item_inspections) {
+                *total += n;
+            }
         }
     }
+    Ok(times_inspected_per_monkey)
+}
+
+fn traced_rounds<'a, W: WorryLevel + 'a>(
+    monkeys: &'a [Monkey],
+    config: &Config,
+    worry_level: impl Fn(u64) -> W,
+) -> Box<dyn Iterator<Item = Result<Round>> + 'a> {
+    let mut troop = Troop::new(monkeys, config.relief, worry_level);
+    Box::new((1..=config.rounds).map(move |round| troop.traced_round(round)))
+}
+
+/// Snapshots of the monkeys after every round, worry levels are kept track of the way
+/// `config` says.
+pub fn trace<'a>(
+    monkeys: &'a [Monkey],
+    config: &Config,
+) -> Result<Box<dyn Iterator<Item = Result<Round>> + 'a>> {
+    match config.worry {
+        Worry::Exact => Ok(traced_rounds(monkeys, config, BigUint::from)),
+        Worry::Modular => {
+            let modulus = modulus(monkeys, config)?;
+            Ok(traced_rounds(monkeys, config, move |i| {
+                Modular::new(i, modulus)
+            }))
+        }
+    }
+}
+
+/// Least common multiple of the test divisors.
+fn modulus(monkeys: &[Monkey], config: &Config) -> Result<u64> {
+    if config.relief.is_some() {
+        return Err(anyhow!(
+            "Worry levels modulo the test divisors can't be relieved"
+        ));
+    }
+    Ok(monkeys
+        .iter()
+        .map(|monkey| monkey.test.divisible_by as u64)
+        .fold(1, |acc, d| acc.lcm(&d)))
+}
+
+fn monkey_business(monkeys: &[Monkey], config: &Config) -> Result<u128> {
+    let mut times_inspected_per_monkey = match config.worry {
+        Worry::Exact => inspections(monkeys, config, BigUint::from)?,
+        Worry::Modular => {
+            let modulus = modulus(monkeys, config)?;
+            inspections(monkeys, config, |i| Modular::new(i, modulus))?
+        }
+    };
 
     times_inspected_per_monkey.sort();
 
-    Ok(times_inspected_per_monkey.iter().rev().take(2).product())
+    Ok(times_inspected_per_monkey
+        .iter()
+        .rev()
+        .take(2)
+        .map(|&n| n as u128)
+        .product())
+}
+
+pub fn get_monkey_business(input: &str, config: &Config) -> Result<u128> {
+    monkey_business(&parse_monkeys(input)?, config)
+}
+
+pub fn get_trace(input: &str, config: &Config) -> Result<Trace> {
+    let monkeys = parse_monkeys(input)?;
+    let rounds = trace(&monkeys, config)?.collect::<Result<Vec<_>>>()?;
+    Ok(Trace { rounds })
+}
+
+pub struct Day11;
+
+impl Solver for Day11 {
+    type Input = Vec<Monkey>;
+    type Answer1 = u128;
+    type Answer2 = u128;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_monkeys(input)
+    }
+
+    fn part1(monkeys: &Self::Input) -> Result<Self::Answer1> {
+        monkey_business(monkeys, &Config::PART1)
+    }
+
+    fn part2(monkeys: &Self::Input) -> Result<Self::Answer2> {
+        monkey_business(monkeys, &Config::PART2)
+    }
 }
 
 #[cfg(test)]
@@ -353,15 +865,247 @@ mod tests {
 
     #[test]
     fn part1() {
-        let res = get_monkey_business(TEST_INPUT, 20, true);
+        let res = get_monkey_business(TEST_INPUT, &Config::PART1);
         assert!(res.is_ok());
         assert_eq!(res.unwrap(), 10605);
     }
 
     #[test]
     fn part2() {
-        let res = get_monkey_business(TEST_INPUT, 10000, false);
+        let res = get_monkey_business(TEST_INPUT, &Config::PART2);
         assert!(res.is_ok());
         assert_eq!(res.unwrap(), 2713310158);
     }
+
+    #[test]
+    fn expressions() {
+        let res = parse_operation::<nom::error::Error<Span>>(Span::new(
+            "  Operation: new = (old + 3) * old - 10 / 2 % 3\n",
+        ));
+        assert!(res.is_ok());
+        let (_, expr) = res.unwrap();
+        assert_eq!(expr.to_string(), "(((old + 3) * old) - ((10 / 2) % 3))");
+        assert_eq!(expr.eval(&5).unwrap(), 38);
+        assert_eq!(expr.eval(&5i64).unwrap(), 38);
+
+        let res = expr.eval(&BigUint::from(1234u32));
+        assert!(res.is_ok());
+        assert_eq!(
+            res.unwrap(),
+            BigUint::from((1234u32 + 3) * 1234 - 10 / 2 % 3)
+        );
+
+        let (_, expr) =
+            parse_expr::<nom::error::Error<Span>>(Span::new("(old + 3) * old - 10 % 3")).unwrap();
+        let modulus = 2 * 3 * 5 * 7 * 11;
+        let res = expr.eval(&Modular::new(1234, modulus));
+        assert!(res.is_ok());
+        assert_eq!(
+            res.unwrap(),
+            Modular::new((1234 + 3) * 1234 - 10 % 3, modulus)
+        );
+
+        let expr = Expr::BinOp(Box::new(Expr::Old), BinOp::Rem, Box::new(Expr::Int(7)));
+        assert_eq!(
+            expr.eval(&Modular::new(30, modulus)).unwrap(),
+            Modular::new(2, modulus)
+        );
+        let expr = Expr::BinOp(Box::new(Expr::Old), BinOp::Rem, Box::new(Expr::Int(13)));
+        assert!(expr.eval(&Modular::new(30, modulus)).is_err());
+        let expr = Expr::BinOp(
+            Box::new(Expr::Old),
+            BinOp::Rem,
+            Box::new(Expr::Int(modulus as u32)),
+        );
+        assert_eq!(
+            expr.eval(&Modular::new(1234, modulus)).unwrap(),
+            Modular::new(1234, modulus)
+        );
+        assert_eq!(
+            expr.eval(&BigUint::from(1234u32)).unwrap(),
+            BigUint::from(1234u32 % modulus as u32)
+        );
+        let expr = Expr::BinOp(
+            Box::new(Expr::Old),
+            BinOp::Rem,
+            Box::new(Expr::Int(2 * modulus as u32)),
+        );
+        assert!(expr.eval(&Modular::new(1234, modulus)).is_err());
+        let expr = Expr::BinOp(Box::new(Expr::Old), BinOp::Div, Box::new(Expr::Int(3)));
+        assert!(expr.eval(&Modular::new(30, modulus)).is_err());
+        assert!(expr.eval(&30).is_ok());
+    }
+
+    #[test]
+    fn custom_operations() {
+        let input = TEST_INPUT
+            .replace("new = old * 19", "new = (old * 19 - 1) % 23 + old")
+            .replace("new = old + 6", "new = old + 6 - (1)");
+        let res = get_monkey_business(input.as_str(), &Config::PART1);
+        assert!(res.is_ok());
+        assert_eq!(res.unwrap(), 10000);
+    }
+
+    #[test]
+    fn worry_modes() {
+        let exact = Config {
+            rounds: 20,
+            relief: None,
+            worry: Worry::Exact,
+        };
+        let modular = Config {
+            worry: Worry::Modular,
+            ..exact
+        };
+        let res = get_monkey_business(TEST_INPUT, &exact);
+        assert!(res.is_ok());
+        assert_eq!(res.unwrap(), 103 * 99);
+        assert_eq!(get_monkey_business(TEST_INPUT, &modular).unwrap(), 103 * 99);
+
+        let relieved = Config {
+            relief: Some(3),
+            ..modular
+        };
+        assert!(get_monkey_business(TEST_INPUT, &relieved).is_err());
+    }
+
+    #[test]
+    fn trace() {
+        let res = get_trace(TEST_INPUT, &Config::PART1);
+        assert!(res.is_ok());
+        let trace = res.unwrap();
+        assert_eq!(trace.rounds.len(), 20);
+
+        // After round 1 in the puzzle: Monkey 0: 20, 23, 27, 26 and Monkey 1: 2080, 25,
+        // 167, 207, 401, 1046
+        let round = &trace.rounds[0];
+        let worries = |monkey: usize| {
+            round.held[monkey]
+                .iter()
+                .map(|held| held.worry.as_str())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(worries(0), vec!["20", "23", "27", "26"]);
+        assert_eq!(worries(1), vec!["2080", "25", "167", "207", "401", "1046"]);
+        assert!(round.held[2].is_empty() && round.held[3].is_empty());
+        assert_eq!(round.inspections, vec![2, 4, 3, 5]);
+        assert_eq!(
+            round.throws[0],
+            Throw {
+                item: 0,
+                from: 0,
+                to: 3,
+                worry: "500".to_string()
+            }
+        );
+        assert_eq!(trace.rounds[19].inspections, vec![101, 95, 7, 105]);
+
+        let csv = trace.monkeys_csv();
+        assert_eq!(csv.lines().count(), 1 + 20 * 4);
+        assert!(csv.contains("\n1,0,2,2 3 4 5\n"));
+        let csv = trace.throws_csv();
+        assert!(csv.starts_with("round,item,from,to,worry\n1,0,0,3,500\n"));
+
+        let res = trace.to_json();
+        assert!(res.is_ok());
+        let json: serde_json::Value = serde_json::from_str(&res.unwrap()).unwrap();
+        assert_eq!(json["rounds"][19]["inspections"][3], 105);
+    }
+
+    #[test]
+    fn many_rounds() {
+        let monkeys = parse_monkeys(TEST_INPUT).unwrap();
+        let modulus = modulus(&monkeys, &Config::PART2).unwrap();
+
+        for rounds in [1, 20, 1000, 12345] {
+            let config = Config {
+                rounds,
+                ..Config::PART2
+            };
+            let res = inspections(&monkeys, &config, |i| Modular::new(i, modulus));
+            assert!(res.is_ok());
+
+            let mut troop = Troop::new(&monkeys, None, |i| Modular::new(i, modulus));
+            for _ in 0..rounds {
+                troop.round(|_, _, _, _| {}).unwrap();
+            }
+            assert_eq!(res.unwrap(), troop.inspections, "{rounds} rounds");
+        }
+
+        // The whole troop goes around in a cycle too, once all of its items do
+        let mut troop = Troop::new(&monkeys, None, |i| Modular::new(i, modulus));
+        let mut seen = HashMap::new();
+        let mut totals = vec![troop.inspections.clone()];
+        let (start, length) = loop {
+            let round = totals.len() - 1;
+            if let Some(start) = seen.insert(troop.items.clone(), round) {
+                break (start, round - start);
+            }
+            troop.round(|_, _, _, _| {}).unwrap();
+            totals.push(troop.inspections.clone());
+        };
+
+        let rounds = 5_000_000_000;
+        let (cycles, rest) = ((rounds - start) / length, (rounds - start) % length);
+        let expected = (0..monkeys.len())
+            .map(|m| {
+                totals[start + rest][m]
+                    + cycles as u64 * (totals[start + length][m] - totals[start][m])
+            })
+            .collect::<Vec<_>>();
+        let config = Config {
+            rounds,
+            ..Config::PART2
+        };
+        let res = inspections(&monkeys, &config, |i| Modular::new(i, modulus));
+        assert!(res.is_ok());
+        assert_eq!(res.unwrap(), expected);
+    }
+
+    #[test]
+    fn validation() {
+        let error = |input: &str| {
+            parse_monkeys(input)
+                .unwrap_err()
+                .downcast::<MonkeyError>()
+                .unwrap()
+        };
+
+        assert!(parse_monkeys(TEST_INPUT).is_ok());
+        assert_eq!(
+            error(&TEST_INPUT.replacen("Monkey 1:", "Monkey 4:", 1)),
+            MonkeyError::OutOfOrder {
+                line: 8,
+                expected: 1,
+                found: 4
+            }
+        );
+        assert_eq!(
+            error(&TEST_INPUT.replacen("throw to monkey 3", "throw to monkey 7", 1)),
+            MonkeyError::MissingTarget {
+                line: 6,
+                monkey: 0,
+                target: 7
+            }
+        );
+        assert_eq!(
+            error(&TEST_INPUT.replacen("throw to monkey 2", "throw to monkey 0", 1)),
+            MonkeyError::SelfThrow { line: 5, monkey: 0 }
+        );
+        assert_eq!(
+            error(&TEST_INPUT.replacen("divisible by 19", "divisible by 0", 1)),
+            MonkeyError::ZeroDivisor {
+                line: 11,
+                monkey: 1
+            }
+        );
+        assert_eq!(
+            error(&TEST_INPUT.replacen("throw to monkey 3", "throw to monkey 7", 1)).to_string(),
+            "line 6: Monkey 0 throws to nonexisting Monkey 7"
+        );
+
+        // Malformed input is reported rather than panicking
+        let res = parse_monkeys(&TEST_INPUT.replacen("old * 19", "old * ", 1));
+        assert!(res.unwrap_err().is::<util::ParseReport>());
+    }
 }
@@ -0,0 +1,42 @@
+#![feature(iter_intersperse)]
+
+use day12::get_rendered_path;
+
+use std::{
+    env,
+    io::{self, BufRead},
+};
+
+use anyhow::{anyhow, Result};
+
+fn main() -> Result<()> {
+    let mut args = env::args().skip(1);
+    let all = match args.next().as_deref() {
+        None | Some("1") => false,
+        Some("2") => true,
+        Some(part) => return Err(anyhow!("Unknown part: {part}")),
+    };
+    let color = match args.next().as_deref() {
+        None | Some("color") => true,
+        Some("plain") => false,
+        Some(mode) => return Err(anyhow!("Unknown mode {mode}, expected color or plain")),
+    };
+
+    let stdin = io::stdin();
+
+    let rendered = get_rendered_path(
+        stdin
+            .lock()
+            .lines()
+            .intersperse_with(|| Ok("\n".to_string()))
+            .collect::<std::result::Result<String, _>>()
+            .map_err(anyhow::Error::msg)?
+            .as_str(),
+        all,
+        color,
+    )?;
+
+    println!("{rendered}");
+
+    Ok(())
+}
@@ -4,12 +4,12 @@ use std::{rc::Rc, str::FromStr};
 
 use anyhow::{anyhow, Context, Result};
 use itertools::{iproduct, Itertools};
-use petgraph::algo::dijkstra;
-use petgraph::graph::NodeIndex;
+use petgraph::algo::astar;
 use petgraph::prelude::Graph;
+use util::Solver;
 
 #[derive(Debug, Clone)]
-struct Heightmap {
+pub struct Heightmap {
     heights: Vec<Vec<i32>>,
     start: (usize, usize),
     end: (usize, usize),
@@ -27,6 +27,65 @@ impl Heightmap {
     fn ind(&self, row: usize, col: usize) -> usize {
         row * self.cols() + col
     }
+
+    fn cell(&self, ind: usize) -> (usize, usize) {
+        (ind / self.cols(), ind % self.cols())
+    }
+
+    /// Draws `path` over the map like the puzzle text does, with an arrow on every
+    /// cell pointing to the next one. With `color`, each cell gets an ANSI background
+    /// shade by elevation, darkest at `a`. Fails if a cell is off the map or a step isn't
+    /// to a neighbouring cell.
+    pub fn render(&self, path: &[(usize, usize)], color: bool) -> Result<String> {
+        if let Some(cell) = path
+            .iter()
+            .find(|(row, col)| *row >= self.rows() || *col >= self.cols())
+        {
+            return Err(anyhow!("Path goes off the map at {cell:?}"));
+        }
+
+        let mut grid = vec![vec!['.'; self.cols()]; self.rows()];
+        for (&(r1, c1), &(r2, c2)) in path.iter().tuple_windows() {
+            grid[r1][c1] = match (r2 as i64 - r1 as i64, c2 as i64 - c1 as i64) {
+                (-1, 0) => '^',
+                (1, 0) => 'v',
+                (0, -1) => '<',
+                (0, 1) => '>',
+                _ => {
+                    return Err(anyhow!(
+                        "Path steps from {:?} to {:?}, which are not neighbours",
+                        (r1, c1),
+                        (r2, c2)
+                    ))
+                }
+            };
+        }
+        grid[self.end.0][self.end.1] = 'E';
+
+        Ok(grid
+            .iter()
+            .zip(self.heights.iter())
+            .map(|(row, heights)| {
+                if !color {
+                    return row.iter().collect::<String>();
+                }
+                let mut line = row
+                    .iter()
+                    .zip(heights)
+                    .map(|(c, h)| {
+                        let shade = 232 + (h - 'a' as i32) * 23 / 25;
+                        if *c == '.' {
+                            format!("\x1b[48;5;{shade}m{c}")
+                        } else {
+                            format!("\x1b[48;5;{shade};1;31m{c}\x1b[22;39m")
+                        }
+                    })
+                    .collect::<String>();
+                line.push_str("\x1b[0m");
+                line
+            })
+            .join("\n"))
+    }
 }
 
 impl FromStr for Heightmap {
@@ -114,20 +173,20 @@ impl From<Heightmap> for HeightmapGraph {
     }
 }
 
-pub fn get_shortest_path_len(input: &str, all: bool) -> Result<i32> {
-    let heightmap = Heightmap::from_str(input)?;
-    let heightmap = Rc::new(heightmap);
+/// Cells of a shortest path to the end, from the start or, with `all`, from whichever
+/// lowest point is closest.
+fn shortest_path(heightmap: &Heightmap, all: bool) -> Result<Vec<(usize, usize)>> {
+    let map = Rc::new(heightmap.clone());
 
     let starting_nodes = if all {
-        heightmap
-            .heights
+        map.heights
             .iter()
             .enumerate()
             .flat_map(|(i, row)| {
-                let heightmap = heightmap.clone();
+                let map = map.clone();
                 row.iter().enumerate().filter_map(move |(j, val)| {
                     if *val == ('a' as i32) {
-                        Some((heightmap.ind(i, j) as u32).into())
+                        Some((map.ind(i, j) as u32).into())
                     } else {
                         None
                     }
@@ -135,30 +194,68 @@ pub fn get_shortest_path_len(input: &str, all: bool) -> Result<i32> {
             })
             .collect::<Vec<_>>()
     } else {
-        vec![(heightmap.ind(heightmap.start.0, heightmap.start.1) as u32).into()]
+        vec![(map.ind(map.start.0, map.start.1) as u32).into()]
     };
 
-    let heightmap_graph = HeightmapGraph::from(Rc::try_unwrap(heightmap).unwrap());
+    let heightmap_graph = HeightmapGraph::from(Rc::try_unwrap(map).unwrap());
 
-    let mut paths = starting_nodes
+    let (_, path) = starting_nodes
         .into_iter()
         .filter_map(|starting_node| {
-            let node_map = dijkstra(
+            astar(
                 &heightmap_graph.graph,
                 starting_node,
-                Some((heightmap_graph.end as u32).into()),
+                |node| node.index() == heightmap_graph.end,
                 |_| 1,
-            );
-
-            node_map
-                .get(&NodeIndex::new(heightmap_graph.end as usize))
-                .copied()
+                |_| 0,
+            )
         })
-        .collect::<Vec<_>>();
+        .min_by_key(|(len, _)| *len)
+        .ok_or(anyhow!("No resuls!"))?;
 
-    paths.sort();
+    Ok(path
+        .into_iter()
+        .map(|node| heightmap.cell(node.index()))
+        .collect())
+}
+
+fn shortest_path_len(heightmap: &Heightmap, all: bool) -> Result<i32> {
+    Ok(shortest_path(heightmap, all)?.len() as i32 - 1)
+}
 
-    paths.first().ok_or(anyhow!("No resuls!")).copied()
+pub fn get_shortest_path_len(input: &str, all: bool) -> Result<i32> {
+    shortest_path_len(&Heightmap::from_str(input)?, all)
+}
+
+pub fn get_shortest_path(input: &str, all: bool) -> Result<Vec<(usize, usize)>> {
+    shortest_path(&Heightmap::from_str(input)?, all)
+}
+
+/// The map with a shortest path drawn over it, see [`Heightmap::render`].
+pub fn get_rendered_path(input: &str, all: bool, color: bool) -> Result<String> {
+    let heightmap = Heightmap::from_str(input)?;
+    let path = shortest_path(&heightmap, all)?;
+    heightmap.render(&path, color)
+}
+
+pub struct Day12;
+
+impl Solver for Day12 {
+    type Input = Heightmap;
+    type Answer1 = i32;
+    type Answer2 = i32;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Heightmap::from_str(input)
+    }
+
+    fn part1(heightmap: &Self::Input) -> Result<Self::Answer1> {
+        shortest_path_len(heightmap, false)
+    }
+
+    fn part2(heightmap: &Self::Input) -> Result<Self::Answer2> {
+        shortest_path_len(heightmap, true)
+    }
 }
 
 #[cfg(test)]
@@ -180,4 +277,73 @@ mod tests {
         assert!(res.is_ok());
         assert_eq!(res.unwrap(), 29);
     }
+
+    #[test]
+    fn path() {
+        let res = get_shortest_path(TEST_INPUT, false);
+        assert!(res.is_ok());
+        let path = res.unwrap();
+        assert_eq!(path.len(), 32);
+        assert_eq!(path.first(), Some(&(0, 0)));
+        assert_eq!(path.last(), Some(&(2, 5)));
+
+        let heightmap = Heightmap::from_str(TEST_INPUT).unwrap();
+        for (&(r1, c1), &(r2, c2)) in path.iter().tuple_windows() {
+            assert_eq!(r1.abs_diff(r2) + c1.abs_diff(c2), 1);
+            assert!(heightmap.heights[r2][c2] - heightmap.heights[r1][c1] <= 1);
+        }
+    }
+
+    #[test]
+    fn render() {
+        let heightmap = Heightmap::from_str(TEST_INPUT).unwrap();
+        // The path drawn in the puzzle text
+        let path = [
+            (0, 0),
+            (1, 0),
+            (1, 1),
+            (2, 1),
+            (2, 2),
+            (3, 2),
+            (4, 2),
+            (4, 3),
+            (4, 4),
+            (4, 5),
+            (4, 6),
+            (4, 7),
+            (3, 7),
+            (2, 7),
+            (1, 7),
+            (0, 7),
+            (0, 6),
+            (0, 5),
+            (0, 4),
+            (0, 3),
+            (1, 3),
+            (2, 3),
+            (3, 3),
+            (3, 4),
+            (3, 5),
+            (3, 6),
+            (2, 6),
+            (1, 6),
+            (1, 5),
+            (1, 4),
+            (2, 4),
+            (2, 5),
+        ];
+        assert_eq!(
+            heightmap.render(&path, false).unwrap(),
+            "v..v<<<<\n>v.vv<<^\n.>vv>E^^\n..v>>>^^\n..>>>>>^"
+        );
+
+        let colored = heightmap.render(&path, true).unwrap();
+        assert_eq!(colored.lines().count(), 5);
+        assert!(colored.starts_with("\x1b[48;5;232;1;31mv"));
+
+        assert!(heightmap.render(&[(0, 0), (0, 2)], false).is_err());
+        assert!(heightmap.render(&[(0, 0), (1, 1)], false).is_err());
+        assert!(heightmap.render(&[(4, 7), (4, 8)], false).is_err());
+        assert!(heightmap.render(&[(5, 0), (4, 0)], false).is_err());
+    }
 }
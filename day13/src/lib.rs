@@ -17,7 +17,7 @@ use nom::{
     sequence::delimited,
     IResult,
 };
-use util::{parse_nice, Span};
+use util::{parse_lines, Solver, Span};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum List {
@@ -99,34 +99,16 @@ where
 pub fn get_pair_list_iter(
     input: impl Iterator<Item = String>,
 ) -> Result<impl Iterator<Item = (List, List)>> {
-    Ok(input
-        .chunks(3)
-        .into_iter()
-        .map(|chunk| {
-            chunk
-                .filter_map(|l| {
-                    if !l.is_empty() {
-                        parse_nice(l.as_str(), parse_list)
-                    } else {
-                        None
-                    }
-                })
-                .collect::<Vec<_>>()
-        })
-        .map(|list_vec| -> Result<(List, List)> {
-            let mut list_vec = list_vec.clone();
-            let r = list_vec.pop().ok_or(anyhow!("Missing entry in input"))?;
-            let l = list_vec
-                .pop()
-                .ok_or(anyhow!("Less than two entires in input!"))?;
-            Ok((l, r))
-        })
-        .collect::<Result<Vec<(List, List)>>>()?
-        .into_iter())
+    let lists = parse_lines(&input.collect::<Vec<_>>().join("\n"), parse_list)?;
+    if lists.len() % 2 != 0 {
+        return Err(anyhow!("Missing entry in input"));
+    }
+    Ok(lists.into_iter().tuples())
 }
 
-pub fn get_sum_right_order(input: impl Iterator<Item = String>) -> Result<usize> {
-    Ok(get_pair_list_iter(input)?
+fn sum_right_order(pairs: &[(List, List)]) -> usize {
+    pairs
+        .iter()
         .enumerate()
         .inspect(
             #[allow(unused)]
@@ -136,18 +118,18 @@ pub fn get_sum_right_order(input: impl Iterator<Item = String>) -> Result<usize>
             },
         )
         .filter_map(|(i, (l, r))| (l <= r).then(|| i + 1))
-        .sum())
+        .sum()
 }
 
-pub fn get_decoder_key(input: impl Iterator<Item = String>) -> Result<usize> {
+fn decoder_key(pairs: &[(List, List)]) -> usize {
     let divider_packets = [
         List::List(vec![List::List(vec![List::Integer(2)])]),
         List::List(vec![List::List(vec![List::Integer(6)])]),
     ];
 
-    Ok(get_pair_list_iter(input)?
-        .map(|(l, r)| [l, r])
-        .flatten()
+    pairs
+        .iter()
+        .flat_map(|(l, r)| [l.clone(), r.clone()])
         .chain(divider_packets.iter().cloned())
         .sorted()
         .inspect(
@@ -160,7 +142,37 @@ pub fn get_decoder_key(input: impl Iterator<Item = String>) -> Result<usize> {
         .enumerate()
         .filter(|list| divider_packets.iter().contains(&(list.1)))
         .map(|(i, _)| i + 1)
-        .product())
+        .product()
+}
+
+pub fn get_sum_right_order(input: impl Iterator<Item = String>) -> Result<usize> {
+    Ok(sum_right_order(
+        &get_pair_list_iter(input)?.collect::<Vec<_>>(),
+    ))
+}
+
+pub fn get_decoder_key(input: impl Iterator<Item = String>) -> Result<usize> {
+    Ok(decoder_key(&get_pair_list_iter(input)?.collect::<Vec<_>>()))
+}
+
+pub struct Day13;
+
+impl Solver for Day13 {
+    type Input = Vec<(List, List)>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(get_pair_list_iter(input.lines().map(|l| l.to_string()))?.collect())
+    }
+
+    fn part1(pairs: &Self::Input) -> Result<Self::Answer1> {
+        Ok(sum_right_order(pairs))
+    }
+
+    fn part2(pairs: &Self::Input) -> Result<Self::Answer2> {
+        Ok(decoder_key(pairs))
+    }
 }
 
 #[cfg(test)]
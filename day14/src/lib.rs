@@ -4,7 +4,7 @@
 use std::{collections::HashMap, fmt, str::FromStr};
 use std::{thread, time};
 
-use anyhow::{anyhow, Result};
+use anyhow::Result;
 use nom::{
     bytes::complete::tag,
     character::complete::digit1,
@@ -14,7 +14,7 @@ use nom::{
     sequence::tuple,
     IResult,
 };
-use util::{parse_nice, Span};
+use util::{parse_lines, Solver, Span};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Space {
@@ -179,7 +179,7 @@ impl Cave {
 }
 
 #[derive(Debug, Clone)]
-struct Line {
+pub struct Line {
     points: Vec<(i32, i32)>,
 }
 
@@ -207,21 +207,11 @@ where
 }
 
 fn get_lines(input: impl Iterator<Item = String>) -> Result<impl Iterator<Item = Line>> {
-    Ok(input
-        .map(|l| -> Result<Line> {
-            parse_nice(l.as_str(), parse_line).ok_or(anyhow!("Couldn't parse line!"))
-        })
-        .collect::<Result<Vec<_>>>()?
-        .into_iter())
+    Ok(parse_lines(&input.collect::<Vec<_>>().join("\n"), parse_line)?.into_iter())
 }
 
-pub fn get_num_sand_rest(
-    input: impl Iterator<Item = String>,
-    floor: bool,
-    print: bool,
-) -> Result<usize> {
-    let lines = get_lines(input)?.collect::<Vec<_>>();
-    let mut cave = Cave::from_lines(lines.as_slice());
+fn num_sand_rest(lines: &[Line], floor: bool, print: bool) -> usize {
+    let mut cave = Cave::from_lines(lines);
     let mut progress = Progress::Falling;
     let mut rested = 0;
     let mut sand_ok = cave.add_sand(&(500, 0));
@@ -254,7 +244,36 @@ pub fn get_num_sand_rest(
             _ => (),
         }
     }
-    Ok(rested)
+    rested
+}
+
+pub fn get_num_sand_rest(
+    input: impl Iterator<Item = String>,
+    floor: bool,
+    print: bool,
+) -> Result<usize> {
+    let lines = get_lines(input)?.collect::<Vec<_>>();
+    Ok(num_sand_rest(&lines, floor, print))
+}
+
+pub struct Day14;
+
+impl Solver for Day14 {
+    type Input = Vec<Line>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(get_lines(input.lines().map(|l| l.to_string()))?.collect())
+    }
+
+    fn part1(lines: &Self::Input) -> Result<Self::Answer1> {
+        Ok(num_sand_rest(lines, false, false))
+    }
+
+    fn part2(lines: &Self::Input) -> Result<Self::Answer2> {
+        Ok(num_sand_rest(lines, true, false))
+    }
 }
 
 #[cfg(test)]
@@ -16,10 +16,10 @@ use nom::{
     IResult,
 };
 use rayon::prelude::*;
-use util::{parse_nice, Span};
+use util::{parse_lines, Solver, Span};
 
 #[derive(Debug, Clone, Copy)]
-struct Sensor {
+pub struct Sensor {
     pos: (i64, i64),
     closest_beacon: (i64, i64),
     d: i64,
@@ -86,11 +86,10 @@ where
 }
 
 fn get_sensors(input: impl Iterator<Item = String>) -> Result<Vec<Sensor>> {
-    input
-        .map(|l| -> Result<Sensor> {
-            parse_nice(l.as_str(), parse_sensor).ok_or(anyhow!("Couldn't parse line!"))
-        })
-        .collect::<Result<Vec<_>>>()
+    Ok(parse_lines(
+        &input.collect::<Vec<_>>().join("\n"),
+        parse_sensor,
+    )?)
 }
 
 fn get_sensor_ranges(sensors: &[Sensor], y: i64) -> Vec<Option<(i64, i64)>> {
@@ -111,9 +110,8 @@ fn get_sensor_ranges(sensors: &[Sensor], y: i64) -> Vec<Option<(i64, i64)>> {
         .collect::<Vec<_>>()
 }
 
-pub fn get_num_ruled_out(input: impl Iterator<Item = String>, y: i64) -> Result<usize> {
-    let sensors = get_sensors(input)?;
-    let ranges = get_sensor_ranges(sensors.as_slice(), y);
+fn num_ruled_out(sensors: &[Sensor], y: i64) -> usize {
+    let ranges = get_sensor_ranges(sensors, y);
     let beacons_y = sensors
         .iter()
         .filter_map(|Sensor { closest_beacon, .. }| {
@@ -129,16 +127,14 @@ pub fn get_num_ruled_out(input: impl Iterator<Item = String>, y: i64) -> Result<
 
     let no_beacons_for_sure = line_in_range.difference(&beacons_y).collect::<Vec<_>>();
 
-    Ok(no_beacons_for_sure.len())
+    no_beacons_for_sure.len()
 }
 
-pub fn get_distress_beacon_freq(
-    input: impl Iterator<Item = String>,
-    min: i64,
-    max: i64,
-) -> Result<i64> {
-    let sensors = get_sensors(input)?;
+pub fn get_num_ruled_out(input: impl Iterator<Item = String>, y: i64) -> Result<usize> {
+    Ok(num_ruled_out(&get_sensors(input)?, y))
+}
 
+fn distress_beacon_freq(sensors: &[Sensor], min: i64, max: i64) -> Result<i64> {
     sensors
         .iter()
         .flat_map(|sensor| {
@@ -155,6 +151,34 @@ pub fn get_distress_beacon_freq(
         .map(|(x, y)| 4000000 * x + y)
 }
 
+pub fn get_distress_beacon_freq(
+    input: impl Iterator<Item = String>,
+    min: i64,
+    max: i64,
+) -> Result<i64> {
+    distress_beacon_freq(&get_sensors(input)?, min, max)
+}
+
+pub struct Day15;
+
+impl Solver for Day15 {
+    type Input = Vec<Sensor>;
+    type Answer1 = usize;
+    type Answer2 = i64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        get_sensors(input.lines().map(|l| l.to_string()))
+    }
+
+    fn part1(sensors: &Self::Input) -> Result<Self::Answer1> {
+        Ok(num_ruled_out(sensors, 2000000))
+    }
+
+    fn part2(sensors: &Self::Input) -> Result<Self::Answer2> {
+        distress_beacon_freq(sensors, 0, 4000000)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -174,4 +198,14 @@ mod tests {
         assert!(res.is_ok());
         assert_eq!(res.unwrap(), 56000011);
     }
+
+    #[test]
+    fn bad_input() {
+        let input = TEST_INPUT.replacen("Sensor at x=10,", "Sensor at x=?,", 1);
+        let res = get_num_ruled_out(input.lines().map(|l| l.to_string()), 10);
+        assert!(res.is_err());
+        let report = res.unwrap_err().downcast::<util::ParseReport>().unwrap();
+        assert_eq!((report.line(), report.column()), (5, 13));
+        assert_eq!(report.to_string(), "bad input at line 5, column 13");
+    }
 }
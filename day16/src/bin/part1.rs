@@ -1,4 +1,4 @@
-use day16::get_max_pressure_2;
+use day16::get_max_pressure;
 
 use std::io::{self, BufRead};
 
@@ -7,7 +7,7 @@ use anyhow::Result;
 fn main() -> Result<()> {
     let stdin = io::stdin();
 
-    let res = get_max_pressure_2(stdin.lock().lines().filter_map(|s| s.ok()), 1, 30);
+    let res = get_max_pressure(stdin.lock().lines().filter_map(|s| s.ok()), 1, 30);
 
     println!("{}", res.unwrap());
 
@@ -0,0 +1,32 @@
+use day16::get_best_plan;
+
+use std::{
+    env,
+    io::{self, BufRead},
+};
+
+use anyhow::{Context, Result};
+
+fn main() -> Result<()> {
+    let mut args = env::args().skip(1);
+    let num_agents = match args.next() {
+        Some(n) => n.parse().context("Number of agents must be a number")?,
+        None => 2,
+    };
+    let time = match args.next() {
+        Some(t) => t.parse().context("Time must be a number")?,
+        None => 26,
+    };
+
+    let stdin = io::stdin();
+
+    let plan = get_best_plan(
+        stdin.lock().lines().filter_map(|s| s.ok()),
+        num_agents,
+        time,
+    )?;
+
+    println!("{plan}");
+
+    Ok(())
+}
@@ -1,7 +1,8 @@
 #![feature(try_blocks)]
 #![feature(iter_intersperse)]
 
-use std::collections::{BTreeSet, HashMap};
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
 use std::str::FromStr;
 
 use anyhow::{anyhow, Result};
@@ -20,7 +21,7 @@ use nom::{
 };
 use petgraph::algo::k_shortest_path;
 use petgraph::{Graph, Undirected};
-use util::{parse_nice, Span};
+use util::{parse_lines, Solver, Span};
 
 #[derive(Debug, Clone)]
 struct Valve {
@@ -29,71 +30,199 @@ struct Valve {
     leads_to: Vec<String>,
 }
 
+/// A valve opened by an agent. It's open from the end of `minute` on, releasing
+/// `pressure` in total by the time the clock runs out.
 #[derive(Debug, Clone, PartialEq, Eq)]
-struct Path {
-    times_left: Vec<i32>,
-    currents: Vec<usize>,
-    valves_left: Vec<usize>,
-    histories: Vec<Vec<(i32, usize)>>,
-    pressure_built: i32,
-    min: i32,
-    max: i32,
+pub struct Opening {
+    pub valve: String,
+    pub minute: i32,
+    pub pressure: i32,
 }
-impl PartialOrd for Path {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.min.partial_cmp(&other.min)
-    }
+
+/// The valves each agent opens, in order, and the pressure they release together.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Plan {
+    pub pressure: i32,
+    pub agents: Vec<Vec<Opening>>,
 }
 
-impl Ord for Path {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.min.cmp(&other.min)
+impl fmt::Display for Plan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, openings) in self.agents.iter().enumerate() {
+            writeln!(f, "Agent {}:", i + 1)?;
+            for opening in openings {
+                writeln!(
+                    f,
+                    "  minute {:>2}: open {}, releasing {}",
+                    opening.minute, opening.valve, opening.pressure
+                )?;
+            }
+        }
+        write!(f, "Total pressure released: {}", self.pressure)
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-enum State {
-    Finished(Path),
-    Progressing(Path),
+/// Which view of the valve network [`ValveGraph::to_dot`] draws.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DotGraph {
+    /// Every valve and the tunnels between them, as found in the input.
+    Tunnels,
+    /// The starting valve and the valves with a non-zero rate, connected by edges
+    /// labelled with the number of minutes it takes to walk between them. Edge lengths
+    /// are set to match for `neato` and `fdp` layouts.
+    Compressed,
 }
 
-impl PartialOrd for State {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.path().partial_cmp(&other.path())
-    }
+const AGENT_COLORS: [&str; 6] = ["red", "blue", "darkgreen", "orange", "purple", "brown"];
+
+#[derive(Debug, Clone)]
+pub struct ValveGraph {
+    names: Vec<String>,
+    rates: Vec<u32>,
+    tunnels: Vec<Vec<usize>>,
+    shortest_paths: Vec<HashMap<usize, u32>>,
+    starting: usize,
 }
 
-impl Ord for State {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.path().cmp(&other.path())
+/// Bounds on the pressure a plan can release, used to skip routes that can't be part of
+/// an optimal plan. Looser bounds skip fewer routes, but never change the result.
+pub trait Bound {
+    /// Pressure released by some plan for `num_agents` agents with `time` minutes.
+    fn lower(&self, graph: &ValveGraph, num_agents: usize, time: i32) -> i32;
+
+    /// At least the pressure agents with `times_left` minutes can release by opening
+    /// (some of) `valves_left`.
+    fn upper(&self, graph: &ValveGraph, times_left: &[i32], valves_left: &[usize]) -> i32;
+}
+
+/// No bounds at all, every route is walked.
+pub struct Exhaustive;
+
+impl Bound for Exhaustive {
+    fn lower(&self, _: &ValveGraph, _: usize, _: i32) -> i32 {
+        0
+    }
+
+    fn upper(&self, _: &ValveGraph, _: &[i32], _: &[usize]) -> i32 {
+        i32::MAX
     }
 }
 
-impl State {
-    fn path(&self) -> &Path {
-        match self {
-            Self::Finished(path) => path,
-            Self::Progressing(path) => path,
+/// Greedy bounds: the lower bound lets agents open whichever valve releases the most
+/// right away, the upper bound pretends every valve is two minutes away from the next.
+pub struct Greedy;
+
+impl Bound for Greedy {
+    fn lower(&self, graph: &ValveGraph, num_agents: usize, time: i32) -> i32 {
+        let mut currents = vec![graph.starting; num_agents];
+        let mut times_left = vec![time; num_agents];
+        let mut valves_left = graph.get_interesting_valves();
+        let mut pressure = 0;
+
+        while let Some((released, agent, i, time_left)) = currents
+            .iter()
+            .zip(times_left.iter())
+            .enumerate()
+            .cartesian_product(valves_left.iter().enumerate())
+            .map(|((agent, (&current, &time_left)), (i, &next))| {
+                let time_left = time_left - graph.shortest_paths[current][&next] as i32 - 1;
+                (graph.rates[next] as i32 * time_left, agent, i, time_left)
+            })
+            .filter(|(_, _, _, time_left)| *time_left > 0)
+            .max()
+        {
+            pressure += released;
+            currents[agent] = valves_left.remove(i);
+            times_left[agent] = time_left;
         }
+
+        pressure
     }
 
-    fn is_finished(&self) -> bool {
-        match self {
-            Self::Finished(_) => true,
-            _ => false,
-        }
+    fn upper(&self, graph: &ValveGraph, times_left: &[i32], valves_left: &[usize]) -> i32 {
+        let slots = times_left
+            .iter()
+            .flat_map(|&t| (1..).step_by(2).map(move |i| t - i).take_while(|&t| t > 0))
+            .sorted()
+            .rev();
+
+        valves_left
+            .iter()
+            .map(|&valve| graph.rates[valve] as i32)
+            .sorted()
+            .rev()
+            .zip(slots)
+            .map(|(rate, time_left)| rate * time_left)
+            .sum()
     }
 }
 
-#[derive(Debug, Clone)]
-struct ValveGraph {
-    rates: Vec<u32>,
-    shortest_paths: Vec<HashMap<usize, u32>>,
-    starting: usize,
+/// Valves opened by a single agent, with the time left when each one is opened.
+type Route = Vec<(i32, usize)>;
+
+struct SubsetSearch<'a, B> {
+    graph: &'a ValveGraph,
+    bound: &'a B,
+    valves: &'a [usize],
+    /// Minutes every other agent has, for the upper bound
+    others: Vec<i32>,
+    lower: i32,
+    route: Route,
+    best: Vec<Option<(i32, Route)>>,
+}
+
+impl<B: Bound> SubsetSearch<'_, B> {
+    fn walk(&mut self, current: usize, time_left: i32, opened: usize, pressure: i32) {
+        if self.best[opened]
+            .as_ref()
+            .map_or(true, |(p, _)| pressure > *p)
+        {
+            self.best[opened] = Some((pressure, self.route.clone()));
+        }
+
+        for (i, &next) in self.valves.iter().enumerate() {
+            if opened & (1 << i) != 0 {
+                continue;
+            }
+
+            let next_time_left = time_left - self.graph.shortest_paths[current][&next] as i32 - 1;
+            if next_time_left <= 0 {
+                continue;
+            }
+
+            let next_opened = opened | (1 << i);
+            let next_pressure = pressure + self.graph.rates[next] as i32 * next_time_left;
+            let times_left = self
+                .others
+                .iter()
+                .copied()
+                .chain(std::iter::once(next_time_left))
+                .collect::<Vec<_>>();
+            let valves_left = self
+                .valves
+                .iter()
+                .enumerate()
+                .filter_map(|(j, &valve)| (next_opened & (1 << j) == 0).then_some(valve))
+                .collect::<Vec<_>>();
+            if next_pressure.saturating_add(self.bound.upper(self.graph, &times_left, &valves_left))
+                < self.lower
+            {
+                continue;
+            }
+
+            self.route.push((next_time_left, next));
+            self.walk(next, next_time_left, next_opened, next_pressure);
+            self.route.pop();
+        }
+    }
 }
 
 impl ValveGraph {
     fn new(valves: Vec<Valve>) -> Result<Self> {
+        let names = valves
+            .iter()
+            .map(|valve| valve.name.clone())
+            .collect::<Vec<_>>();
         let rates = valves.iter().map(|valve| valve.rate).collect::<Vec<_>>();
 
         let inds_map = valves
@@ -119,6 +248,13 @@ impl ValveGraph {
                     .collect::<Vec<_>>()
             })
             .collect::<Result<Vec<_>>>()?;
+
+        let mut tunnels = vec![vec![]; rates.len()];
+        for &(from, to) in edges.iter() {
+            tunnels[from as usize].push(to as usize);
+            tunnels[to as usize].push(from as usize);
+        }
+
         let graph: Graph<(), (), Undirected> = Graph::from_edges(edges);
 
         let shortest_paths = (0u32..rates.len() as u32)
@@ -131,7 +267,9 @@ impl ValveGraph {
             .collect::<Vec<_>>();
 
         Ok(ValveGraph {
+            names,
             rates,
+            tunnels,
             shortest_paths,
             starting: *inds_map.get(&"AA".to_string()).unwrap() as usize,
         })
@@ -145,296 +283,241 @@ impl ValveGraph {
             .collect::<Vec<_>>()
     }
 
-    fn max1(&self, times_left: &[i32], valves_left: &[usize]) -> i32 {
-        let mut rates_left = valves_left
-            .iter()
-            .map(|i| self.rates[*i])
-            .collect::<Vec<_>>();
+    fn index_of(&self, name: &str) -> Option<usize> {
+        self.names.iter().position(|n| n == name)
+    }
 
-        let times_left_iter = times_left
-            .iter()
-            .flat_map(|&t| (0..).map(move |i| t - 2 * i).take_while(|&t| t > 0))
-            .sorted()
-            .rev();
+    /// Valves walked through going from one valve to another along a shortest path,
+    /// both ends included.
+    fn tunnel_path(&self, from: usize, to: usize) -> Vec<usize> {
+        let mut previous = vec![None; self.names.len()];
+        let mut queue = VecDeque::from([from]);
+        previous[from] = Some(from);
 
-        rates_left.sort();
-        rates_left
-            .into_iter()
-            .rev()
-            .zip(times_left_iter)
-            .filter_map(|(rate, t_l)| (t_l > 0).then(|| t_l * rate as i32))
-            .sum()
-    }
+        while let Some(current) = queue.pop_front() {
+            if current == to {
+                break;
+            }
+            for &next in self.tunnels[current].iter() {
+                if previous[next].is_none() {
+                    previous[next] = Some(current);
+                    queue.push_back(next);
+                }
+            }
+        }
 
-    fn max2(
-        &self,
-        times_left: &[i32],
-        currents: &[usize],
-        valves_left: &[usize],
-        level: i32,
-    ) -> i32 {
-        valves_left
-            .iter()
-            .flat_map(|&other| {
-                self.get_many_next(times_left, currents, other, valves_left, None)
-                    .map(
-                        |(pressure, new_times_left, new_currents, next_valves_left, _)| {
-                            if level > 0 {
-                                pressure
-                                    + self.max2(
-                                        new_times_left.as_slice(),
-                                        new_currents.as_slice(),
-                                        next_valves_left.as_slice(),
-                                        level - 1,
-                                    )
-                            } else {
-                                pressure
-                                    + self.max1(
-                                        new_times_left.as_slice(),
-                                        next_valves_left.as_slice(),
-                                    )
-                            }
-                        },
-                    )
-            })
-            .max()
-            .unwrap_or(0)
+        let mut path = vec![to];
+        let mut current = to;
+        while let Some(prev) = previous[current].filter(|&prev| prev != current) {
+            path.push(prev);
+            current = prev;
+        }
+        path.reverse();
+        path
     }
 
-    fn min(&self, times_left: &[i32], currents: &[usize], valves_left: &[usize]) -> i32 {
-        if times_left.iter().all(|&t| t == 0) || valves_left.is_empty() {
-            return 0;
+    /// Renders the valve network in Graphviz DOT format. If a plan is given, the edges
+    /// each agent walks along and the valves it opens are drawn in the agent's colour.
+    pub fn to_dot(&self, graph: DotGraph, plan: Option<&Plan>) -> String {
+        let nodes = match graph {
+            DotGraph::Tunnels => (0..self.names.len()).collect::<Vec<_>>(),
+            DotGraph::Compressed => std::iter::once(self.starting)
+                .chain(self.get_interesting_valves())
+                .collect(),
+        };
+
+        let mut opened = HashMap::new();
+        let mut walked = HashMap::new();
+        for (agent, openings) in plan.iter().flat_map(|plan| plan.agents.iter().enumerate()) {
+            let mut current = self.starting;
+            for opening in openings {
+                let Some(next) = self.index_of(&opening.valve) else {
+                    continue;
+                };
+                opened.entry(next).or_insert((agent, opening.minute));
+                let hops = match graph {
+                    DotGraph::Tunnels => self.tunnel_path(current, next),
+                    DotGraph::Compressed => vec![current, next],
+                };
+                for (&a, &b) in hops.iter().tuple_windows() {
+                    walked.entry((a.min(b), a.max(b))).or_insert(agent);
+                }
+                current = next;
+            }
         }
 
-        let (pressure_built, times_left, next, next_valves_left, _) = valves_left
-            .iter()
-            .flat_map(|&other| self.get_many_next(times_left, currents, other, valves_left, None))
-            .sorted_by(|l, r| (r.0).cmp(&l.0))
-            .next()
-            .unwrap();
+        let mut lines = vec!["graph valves {".to_string()];
+        for &node in nodes.iter() {
+            let mut attributes = vec![format!(
+                "label=\"{}\\n{}\"",
+                self.names[node], self.rates[node]
+            )];
+            if node == self.starting {
+                attributes.push("shape=doublecircle".to_string());
+            }
+            if let Some((agent, minute)) = opened.get(&node) {
+                let color = AGENT_COLORS[agent % AGENT_COLORS.len()];
+                attributes.push(format!("color={color}"));
+                attributes.push("penwidth=3".to_string());
+                attributes.push(format!("xlabel=\"minute {minute}\""));
+            }
+            lines.push(format!(
+                "    {} [{}];",
+                self.names[node],
+                attributes.join(", ")
+            ));
+        }
+
+        let edges = match graph {
+            DotGraph::Tunnels => (0..self.names.len())
+                .flat_map(|from| {
+                    self.tunnels[from]
+                        .iter()
+                        .filter(move |&&to| from < to)
+                        .map(move |&to| (from, to, None))
+                })
+                .collect::<Vec<_>>(),
+            DotGraph::Compressed => nodes
+                .iter()
+                .tuple_combinations()
+                .map(|(&a, &b)| (a.min(b), a.max(b), Some(self.shortest_paths[a][&b])))
+                .collect(),
+        };
+        for (from, to, length) in edges {
+            let mut attributes = vec![];
+            if let Some(length) = length {
+                attributes.push(format!("label={length}"));
+                attributes.push(format!("len={length}"));
+            }
+            if let Some(agent) = walked.get(&(from, to)) {
+                let color = AGENT_COLORS[agent % AGENT_COLORS.len()];
+                attributes.push(format!("color={color}"));
+                attributes.push("penwidth=3".to_string());
+            }
+            let attributes = if attributes.is_empty() {
+                String::new()
+            } else {
+                format!(" [{}]", attributes.join(", "))
+            };
+            lines.push(format!(
+                "    {} -- {}{attributes};",
+                self.names[from], self.names[to]
+            ));
+        }
+        lines.push("}".to_string());
 
-        pressure_built + self.min(times_left.as_slice(), next.as_slice(), &next_valves_left)
+        lines.join("\n")
     }
 
-    fn get_many_next<'a>(
-        &'a self,
-        times_left: &'a [i32],
-        currents: &'a [usize],
-        other: usize,
-        valves_left: &'a [usize],
-        histories: Option<&'a [Vec<(i32, usize)>]>,
-    ) -> impl Iterator<
-        Item = (
-            i32,
-            Vec<i32>,
-            Vec<usize>,
-            Vec<usize>,
-            Option<Vec<Vec<(i32, usize)>>>,
-        ),
-    > + 'a {
-        currents
+    fn openings(&self, time: i32, route: &[(i32, usize)]) -> Vec<Opening> {
+        route
             .iter()
-            .zip(times_left.iter())
-            .enumerate()
-            .filter(|(_, (_, time_left))| **time_left != 0)
-            .unique()
-            .map(move |(i, (current, time_left))| {
-                let (path_length, next_valves_left, rate) =
-                    self.get_next(*current, other, valves_left);
-                let next_time_left = (time_left - path_length - 1).max(0);
-                let mut new_currents = currents.to_vec();
-                *new_currents.get_mut(i).unwrap() = other;
-                let mut new_times_left = times_left.to_vec();
-                *new_times_left.get_mut(i).unwrap() = next_time_left;
-                let new_histories = histories.map(|histories| {
-                    let mut new_histories = histories.to_vec();
-                    new_histories
-                        .get_mut(i)
-                        .unwrap()
-                        .push((next_time_left, other));
-                    new_histories
-                });
-                (
-                    rate * next_time_left,
-                    new_times_left,
-                    new_currents,
-                    next_valves_left,
-                    new_histories,
-                )
+            .filter(|(time_left, _)| *time_left > 0)
+            .map(|(time_left, valve)| Opening {
+                valve: self.names[*valve].clone(),
+                minute: time - time_left,
+                pressure: self.rates[*valve] as i32 * time_left,
             })
+            .collect()
     }
 
-    fn get_next(
+    /// Walks every route a single agent can take in `time`, recording the best pressure
+    /// (and the route releasing it) for each set of interesting valves opened on the way.
+    /// Routes that can't be part of an optimal plan for `num_agents` agents are skipped.
+    fn best_per_subset<B: Bound>(
         &self,
-        current: usize,
-        other: usize,
-        valves_left: &[usize],
-    ) -> (i32, Vec<usize>, i32) {
-        let path_length = *self
-            .shortest_paths
-            .get(current)
-            .and_then(|paths| paths.get(&other))
-            .unwrap() as i32;
-        let mut next_valves_left = valves_left.to_vec();
-        next_valves_left.retain(|&x| x != other);
-
-        (path_length, next_valves_left, self.rates[other] as i32)
+        bound: &B,
+        valves: &[usize],
+        num_agents: usize,
+        time: i32,
+    ) -> Vec<Option<(i32, Route)>> {
+        let mut search = SubsetSearch {
+            graph: self,
+            bound,
+            valves,
+            others: vec![time; num_agents.saturating_sub(1)],
+            lower: bound.lower(self, num_agents, time),
+            route: vec![],
+            best: vec![None; 1 << valves.len()],
+        };
+        search.walk(self.starting, time, 0, 0);
+        search.best
     }
 
-    fn make_path(
-        &self,
-        times_left: Vec<i32>,
-        currents: Vec<usize>,
-        mut histories: Vec<Vec<(i32, usize)>>,
-        valves_left: Vec<usize>,
-        pressure_built: i32,
-    ) -> Path {
-        if histories.len() == 0 {
-            histories = times_left
-                .iter()
-                .zip(currents.iter())
-                .map(|(t, c)| vec![(*t, *c)])
-                .collect::<Vec<_>>();
-        }
-
-        let min = pressure_built
-            + self.min(
-                times_left.as_slice(),
-                currents.as_slice(),
-                valves_left.as_slice(),
-            );
-        let max = pressure_built
-            + self.max2(
-                times_left.as_slice(),
-                currents.as_slice(),
-                valves_left.as_slice(),
-                0,
-            );
-        Path {
-            times_left,
-            currents,
-            valves_left,
-            histories,
-            pressure_built,
-            min,
-            max,
-        }
+    /// Exact plan for any number of agents. Every agent opens a disjoint set of valves,
+    /// so the best single agent pressure per subset of valves is computed once and the
+    /// subsets are then split between the agents with a DP over bitmasks.
+    pub fn best_plan(&self, num_agents: usize, time: i32) -> Plan {
+        self.best_plan_with(&Greedy, num_agents, time)
     }
 
-    fn get_max_pressure(&self, num_agents: usize, time: i32) -> i32 {
-        let mut paths = std::iter::once(State::Progressing(self.make_path(
-            vec![time; num_agents],
-            vec![self.starting, self.starting],
-            vec![],
-            self.get_interesting_valves(),
-            0,
-        )))
-        .collect::<BTreeSet<_>>();
-
-        let mut i = 0;
+    /// Same as [`ValveGraph::best_plan`], using `bound` to skip hopeless routes.
+    pub fn best_plan_with<B: Bound>(&self, bound: &B, num_agents: usize, time: i32) -> Plan {
+        let valves = self.get_interesting_valves();
+        let full = (1 << valves.len()) - 1;
+        let best = self.best_per_subset(bound, &valves, num_agents, time);
 
-        while paths
+        // Best single agent pressure opening at most the valves in each subset, and the
+        // subset actually opened to get it
+        let mut at_most = best
             .iter()
-            .filter(|path| !path.is_finished())
-            .next()
-            .is_some()
-        {
-            let min = paths.iter().rev().next().unwrap().path().min;
-            paths.retain(|state| state.path().max >= min);
-
-            if let Some(next_considered) = paths
-                .iter()
-                .rev()
-                .filter(|path| !path.is_finished())
-                .next()
-                .cloned()
-            {
-                paths.retain(|path| path != &next_considered);
-
-                let Path {
-                    times_left,
-                    currents,
-                    valves_left,
-                    pressure_built,
-                    histories,
-                    ..
-                } = next_considered.path();
-                for &other in valves_left.iter() {
-                    for (pressure, new_times_left, new_currents, next_valves_left, new_histories) in
-                        self.get_many_next(
-                            times_left,
-                            currents,
-                            other,
-                            valves_left,
-                            Some(histories.as_slice()),
-                        )
-                    {
-                        let next_valves_len = next_valves_left.len();
-                        let any_times_left = new_times_left.iter().any(|&t| t > 0);
-
-                        let path = self.make_path(
-                            new_times_left,
-                            new_currents,
-                            new_histories.unwrap(),
-                            next_valves_left,
-                            pressure_built + pressure,
-                        );
-                        if next_valves_len == 0 {
-                            paths.insert(State::Finished(path))
-                        } else if any_times_left {
-                            paths.insert(State::Progressing(path))
-                        } else {
-                            paths.insert(State::Finished(path))
-                        };
-                    }
+            .enumerate()
+            .map(|(mask, b)| match b {
+                Some((pressure, _)) => (*pressure, mask),
+                None => (0, 0),
+            })
+            .collect::<Vec<_>>();
+        for bit in 0..valves.len() {
+            for mask in 0..=full {
+                if mask & (1 << bit) != 0 && at_most[mask ^ (1 << bit)].0 > at_most[mask].0 {
+                    at_most[mask] = at_most[mask ^ (1 << bit)];
                 }
             }
-            i += 1;
         }
 
-        paths.iter().rev().next().unwrap().path().pressure_built
-    }
+        // splits[n][mask]: best pressure of n + 1 agents sharing the valves in mask, and
+        // the subset given to the last of them
+        let mut splits = vec![at_most.iter().map(|(p, _)| (*p, 0)).collect::<Vec<_>>()];
+        for n in 1..num_agents {
+            let previous = &splits[n - 1];
+            let split = (0..=full)
+                .map(|mask| {
+                    let mut best_split = (previous[mask].0, 0);
+                    let mut sub = mask;
+                    while sub > 0 {
+                        let pressure = previous[mask ^ sub].0 + at_most[sub].0;
+                        if pressure > best_split.0 {
+                            best_split = (pressure, sub);
+                        }
+                        sub = (sub - 1) & mask;
+                    }
+                    best_split
+                })
+                .collect::<Vec<_>>();
+            splits.push(split);
+        }
 
-    fn traverse(
-        &self,
-        time_left: i32,
-        current_pressure: i32,
-        pressure_built: i32,
-        current: usize,
-        valves_left: Vec<usize>,
-    ) -> i32 {
-        if time_left == 0 {
-            return pressure_built;
+        let mut agents = vec![];
+        let mut mask = full;
+        for n in (0..num_agents).rev() {
+            let share = if n == 0 { mask } else { splits[n][mask].1 };
+            let (_, opened) = at_most[share];
+            let route = best[opened].as_ref().map_or(&[][..], |(_, route)| route);
+            agents.push(self.openings(time, route));
+            mask ^= share;
         }
-        if valves_left.is_empty() {
-            return pressure_built + time_left * current_pressure;
+        agents.reverse();
+
+        Plan {
+            pressure: splits
+                .get(num_agents.wrapping_sub(1))
+                .map_or(0, |split| split[full].0),
+            agents,
         }
+    }
 
-        valves_left
-            .iter()
-            .map(|&other| {
-                let path_length = *self
-                    .shortest_paths
-                    .get(current)
-                    .and_then(|paths| paths.get(&other))
-                    .unwrap() as i32;
-                if path_length >= time_left {
-                    self.traverse(time_left, current_pressure, pressure_built, current, vec![])
-                } else {
-                    let mut next_valves_left = valves_left.clone();
-                    next_valves_left.retain(|&x| x != other);
-                    let new_pressure = current_pressure + self.rates[other] as i32;
-                    self.traverse(
-                        time_left - (path_length + 1),
-                        new_pressure,
-                        pressure_built + current_pressure * (path_length + 1),
-                        other,
-                        next_valves_left,
-                    )
-                }
-            })
-            .max()
-            .unwrap()
+    fn max_pressure(&self, num_agents: usize, time: i32) -> i32 {
+        self.best_plan(num_agents, time).pressure
     }
 }
 
@@ -480,59 +563,297 @@ where
 }
 
 fn parse_valves(input: impl Iterator<Item = String>) -> Result<Vec<Valve>> {
-    input
-        .map(|l| -> Result<Valve> {
-            parse_nice(l.as_str(), parse_valve).ok_or(anyhow!("Couldn't parse line!"))
-        })
-        .collect::<Result<Vec<_>>>()
+    Ok(parse_lines(
+        &input.collect::<Vec<_>>().join("\n"),
+        parse_valve,
+    )?)
 }
 
-pub fn get_max_pressure(input: impl Iterator<Item = String>) -> Result<i32> {
+pub fn get_max_pressure(
+    input: impl Iterator<Item = String>,
+    num_agents: usize,
+    time: i32,
+) -> Result<i32> {
     let valves = parse_valves(input)?;
     let valve_graph = ValveGraph::new(valves)?;
-    Ok(valve_graph.traverse(
-        30,
-        0,
-        0,
-        valve_graph.starting,
-        valve_graph.get_interesting_valves(),
-    ))
+    Ok(valve_graph.max_pressure(num_agents, time))
 }
 
-pub fn get_max_pressure_2(
+pub fn get_best_plan(
     input: impl Iterator<Item = String>,
     num_agents: usize,
     time: i32,
-) -> Result<i32> {
+) -> Result<Plan> {
     let valves = parse_valves(input)?;
     let valve_graph = ValveGraph::new(valves)?;
-    Ok(valve_graph.get_max_pressure(num_agents, time))
+    Ok(valve_graph.best_plan(num_agents, time))
+}
+
+/// DOT for the valve network, highlighting the best plan for `num_agents` agents with
+/// `time` minutes if given.
+pub fn get_dot(
+    input: impl Iterator<Item = String>,
+    graph: DotGraph,
+    plan: Option<(usize, i32)>,
+) -> Result<String> {
+    let valves = parse_valves(input)?;
+    let valve_graph = ValveGraph::new(valves)?;
+    let plan = plan.map(|(num_agents, time)| valve_graph.best_plan(num_agents, time));
+    Ok(valve_graph.to_dot(graph, plan.as_ref()))
+}
+
+pub struct Day16;
+
+impl Solver for Day16 {
+    type Input = ValveGraph;
+    type Answer1 = i32;
+    type Answer2 = i32;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        ValveGraph::new(parse_valves(input.lines().map(|l| l.to_string()))?)
+    }
+
+    fn part1(valve_graph: &Self::Input) -> Result<Self::Answer1> {
+        Ok(valve_graph.max_pressure(1, 30))
+    }
+
+    fn part2(valve_graph: &Self::Input) -> Result<Self::Answer2> {
+        Ok(valve_graph.max_pressure(2, 26))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    use std::collections::BTreeSet;
+
     const TEST_INPUT: &str = include_str!("../data/test_input");
 
     #[test]
-    fn part1old() {
-        let res = get_max_pressure(TEST_INPUT.lines().map(|l| l.to_string()));
+    fn part1() {
+        let res = get_max_pressure(TEST_INPUT.lines().map(|l| l.to_string()), 1, 30);
         assert!(res.is_ok());
         assert_eq!(res.unwrap(), 1651);
     }
 
     #[test]
-    fn part1() {
-        let res = get_max_pressure_2(TEST_INPUT.lines().map(|l| l.to_string()), 1, 30);
+    fn part2() {
+        let res = get_max_pressure(TEST_INPUT.lines().map(|l| l.to_string()), 2, 26);
         assert!(res.is_ok());
-        assert_eq!(res.unwrap(), 1651);
+        assert_eq!(res.unwrap(), 1707);
     }
 
     #[test]
-    fn part2() {
-        let res = get_max_pressure_2(TEST_INPUT.lines().map(|l| l.to_string()), 2, 26);
+    fn plan() {
+        let res = get_best_plan(TEST_INPUT.lines().map(|l| l.to_string()), 1, 30);
         assert!(res.is_ok());
-        assert_eq!(res.unwrap(), 1707);
+        let plan = res.unwrap();
+        assert_eq!(plan.pressure, 1651);
+        assert_eq!(
+            plan.agents[0]
+                .iter()
+                .map(|o| (o.valve.as_str(), o.minute))
+                .collect::<Vec<_>>(),
+            vec![
+                ("DD", 2),
+                ("BB", 5),
+                ("JJ", 9),
+                ("HH", 17),
+                ("EE", 21),
+                ("CC", 24)
+            ]
+        );
+        assert_eq!(plan.agents[0].iter().map(|o| o.pressure).sum::<i32>(), 1651);
+
+        let res = get_best_plan(TEST_INPUT.lines().map(|l| l.to_string()), 2, 26);
+        assert!(res.is_ok());
+        let plan = res.unwrap();
+        assert_eq!(plan.agents.len(), 2);
+        assert_eq!(
+            plan.agents
+                .iter()
+                .flatten()
+                .map(|o| o.pressure)
+                .sum::<i32>(),
+            1707
+        );
+    }
+
+    /// Best pressure found by simulating every agent minute by minute on the tunnels.
+    fn brute_force(
+        graph: &ValveGraph,
+        memo: &mut HashMap<(i32, Vec<usize>, u64), i32>,
+        time_left: i32,
+        currents: Vec<usize>,
+        opened: u64,
+    ) -> i32 {
+        if time_left == 0 {
+            return 0;
+        }
+        let key = (time_left, currents.clone(), opened);
+        if let Some(&pressure) = memo.get(&key) {
+            return pressure;
+        }
+
+        // Every agent either opens the valve it's at, walks a tunnel or waits
+        let mut best = 0;
+        let mut moves = vec![(currents.clone(), opened, 0)];
+        for (agent, &current) in currents.iter().enumerate() {
+            moves = moves
+                .into_iter()
+                .flat_map(|(positions, opened, pressure)| {
+                    let mut next = vec![(positions.clone(), opened, pressure)];
+                    if graph.rates[current] > 0 && opened & (1 << current) == 0 {
+                        next.push((
+                            positions.clone(),
+                            opened | (1 << current),
+                            pressure + graph.rates[current] as i32 * (time_left - 1),
+                        ));
+                    }
+                    for &to in graph.tunnels[current].iter() {
+                        let mut positions = positions.clone();
+                        positions[agent] = to;
+                        next.push((positions, opened, pressure));
+                    }
+                    next
+                })
+                .collect();
+        }
+        for (mut positions, opened, pressure) in moves {
+            positions.sort();
+            best = best.max(pressure + brute_force(graph, memo, time_left - 1, positions, opened));
+        }
+
+        memo.insert(key, best);
+        best
+    }
+
+    /// Random connected network of up to 8 valves, in puzzle input format.
+    fn random_network(seed: &mut u64) -> String {
+        let mut next = |n: u64| {
+            *seed ^= *seed << 13;
+            *seed ^= *seed >> 7;
+            *seed ^= *seed << 17;
+            (*seed % n) as usize
+        };
+
+        let size = 2 + next(7);
+        let names = (0..size)
+            .map(|i| format!("{}A", (b'A' + i as u8) as char))
+            .collect::<Vec<_>>();
+        let mut tunnels = vec![BTreeSet::new(); size];
+        for i in 1..size {
+            for j in [next(i as u64), next(size as u64)] {
+                if i != j {
+                    tunnels[i].insert(j);
+                    tunnels[j].insert(i);
+                }
+            }
+        }
+
+        (0..size)
+            .map(|i| {
+                let rate = if next(2) == 0 { 0 } else { 1 + next(25) };
+                let to = tunnels[i].iter().map(|&j| names[j].as_str()).join(", ");
+                if tunnels[i].len() == 1 {
+                    format!(
+                        "Valve {} has flow rate={rate}; tunnel leads to valve {to}",
+                        names[i]
+                    )
+                } else {
+                    format!(
+                        "Valve {} has flow rate={rate}; tunnels lead to valves {to}",
+                        names[i]
+                    )
+                }
+            })
+            .join("\n")
+    }
+
+    #[test]
+    fn random_networks() {
+        let mut seed = 0x2545_f491_4f6c_dd1d;
+        for _ in 0..50 {
+            let input = random_network(&mut seed);
+            let valve_graph = Day16::parse(input.as_str()).unwrap();
+
+            for num_agents in 1..=2 {
+                for time in [5, 9] {
+                    let expected = brute_force(
+                        &valve_graph,
+                        &mut HashMap::new(),
+                        time,
+                        vec![valve_graph.starting; num_agents],
+                        0,
+                    );
+
+                    for plan in [
+                        valve_graph.best_plan_with(&Exhaustive, num_agents, time),
+                        valve_graph.best_plan_with(&Greedy, num_agents, time),
+                    ] {
+                        assert_eq!(
+                            plan.pressure, expected,
+                            "{num_agents} agent(s), {time} minutes, network:\n{input}"
+                        );
+                        assert_eq!(
+                            plan.agents
+                                .iter()
+                                .flatten()
+                                .map(|o| o.pressure)
+                                .sum::<i32>(),
+                            expected
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn many_agents() {
+        let valve_graph = Day16::parse(TEST_INPUT).unwrap();
+        let plan = valve_graph.best_plan(4, 26);
+        let openings = plan.agents.iter().flatten().collect::<Vec<_>>();
+        assert_eq!(plan.agents.len(), 4);
+        assert_eq!(
+            openings.iter().map(|o| o.pressure).sum::<i32>(),
+            plan.pressure
+        );
+        assert_eq!(
+            openings.iter().map(|o| &o.valve).unique().count(),
+            openings.len()
+        );
+        assert!(plan.pressure >= 1707);
+    }
+
+    #[test]
+    fn dot() {
+        let res = get_dot(
+            TEST_INPUT.lines().map(|l| l.to_string()),
+            DotGraph::Tunnels,
+            None,
+        );
+        assert!(res.is_ok());
+        let dot = res.unwrap();
+        assert!(dot.starts_with("graph valves {"));
+        assert_eq!(dot.matches(" -- ").count(), 10);
+        assert!(dot.contains("    AA -- DD;"));
+
+        let res = get_dot(
+            TEST_INPUT.lines().map(|l| l.to_string()),
+            DotGraph::Compressed,
+            Some((1, 30)),
+        );
+        assert!(res.is_ok());
+        let dot = res.unwrap();
+        // AA and the 6 valves with a non-zero rate, all connected to each other
+        assert_eq!(dot.matches(" -- ").count(), 21);
+        assert!(dot.contains("    AA -- DD [label=1, len=1, color=red, penwidth=3];"));
+        assert!(dot.contains("    BB -- JJ [label=3, len=3, color=red, penwidth=3];"));
+        assert!(
+            dot.contains("    CC [label=\"CC\\n2\", color=red, penwidth=3, xlabel=\"minute 24\"];")
+        );
+        assert!(!dot.contains("    GG "));
     }
 }
@@ -0,0 +1,37 @@
+use day17::get_cycle_report;
+
+use std::{
+    env,
+    io::{self, BufRead},
+};
+
+use anyhow::{Context, Result};
+
+const MAX_ROCKS: i64 = 1_000_000;
+
+fn main() -> Result<()> {
+    let verify_rocks = env::args()
+        .nth(1)
+        .map(|n| n.parse())
+        .transpose()
+        .context("Number of rocks to verify up to must be a number")?;
+
+    let stdin = io::stdin();
+
+    let report = get_cycle_report(
+        stdin.lock().lines().filter_map(|s| s.ok()),
+        MAX_ROCKS,
+        verify_rocks,
+    )?;
+
+    println!("{report}");
+    println!(
+        "Height after 1000000000000 rocks: {}",
+        report.height_after(1000000000000)
+    );
+    if let Some(verify_rocks) = verify_rocks {
+        println!("Verified against a simulation of {verify_rocks} rocks");
+    }
+
+    Ok(())
+}
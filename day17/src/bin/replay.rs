@@ -0,0 +1,57 @@
+use day17::get_replay;
+
+use std::{
+    env, fs,
+    io::{self, BufRead, Write},
+    thread,
+    time::Duration,
+};
+
+use anyhow::{Context, Result};
+
+fn main() -> Result<()> {
+    let mut args = env::args().skip(1);
+    let num_rocks = match args.next() {
+        Some(n) => n.parse().context("Number of rocks must be a number")?,
+        None => 10,
+    };
+    let fps: f64 = match args.next() {
+        Some(fps) => fps.parse().context("Frame rate must be a number")?,
+        None => 10.0,
+    };
+    let window_height = match args.next() {
+        Some(h) => h.parse().context("Window height must be a number")?,
+        None => 20,
+    };
+    let frames_path = args.next();
+
+    let stdin = io::stdin();
+
+    let replay = get_replay(
+        stdin.lock().lines().filter_map(|s| s.ok()),
+        num_rocks,
+        window_height,
+    )?;
+
+    // Dumping frames skips the animation, they're all written to the file at once
+    if let Some(path) = frames_path {
+        let frames = replay.collect::<Vec<_>>();
+        fs::write(&path, frames.join("\n\n") + "\n")
+            .with_context(|| format!("Unable to write frames to {path}"))?;
+        println!("Wrote {} frames to {path}", frames.len());
+        return Ok(());
+    }
+
+    let delay = (fps > 0.0).then(|| Duration::from_secs_f64(1.0 / fps));
+    let mut stdout = io::stdout().lock();
+    for frame in replay {
+        // Clear the screen and move the cursor to the top left corner first
+        writeln!(stdout, "\x1b[2J\x1b[H{frame}")?;
+        stdout.flush()?;
+        if let Some(delay) = delay {
+            thread::sleep(delay);
+        }
+    }
+
+    Ok(())
+}
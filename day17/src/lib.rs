@@ -1,91 +1,140 @@
-#![feature(int_roundings)]
-
-use std::{
-    array::IntoIter,
-    collections::HashMap,
-    fmt,
-    iter::{Cycle, Enumerate, Peekable},
-};
+use std::{collections::HashMap, fmt, str::FromStr};
 
 use anyhow::{anyhow, Result};
-use static_init::{dynamic, LazyAccess};
-
-#[dynamic]
-static ROCK1: Rock = Rock {
-    occupied: vec![vec![true, true, true, true]],
-};
-
-#[dynamic]
-static ROCK2: Rock = Rock {
-    occupied: vec![
-        vec![false, true, false],
-        vec![true, true, true],
-        vec![false, true, false],
-    ],
-};
-
-#[dynamic]
-static ROCK3: Rock = Rock {
-    occupied: vec![
-        vec![true, true, true],
-        vec![false, false, true],
-        vec![false, false, true],
-    ],
-};
-
-#[dynamic]
-static ROCK4: Rock = Rock {
-    occupied: vec![vec![true], vec![true], vec![true], vec![true]],
-};
-
-#[dynamic]
-static ROCK5: Rock = Rock {
-    occupied: vec![vec![true, true], vec![true, true]],
-};
-
-#[derive(Debug, PartialEq, Eq)]
-struct Rock {
-    // filled, x -> right, y -> top
+use util::Solver;
+
+/// The rocks of the puzzle, in the order they fall.
+const DEFAULT_ROCKS: &str = "\
+####
+
+.#.
+###
+.#.
+
+..#
+..#
+###
+
+#
+#
+#
+#
+
+##
+##";
+
+pub const DEFAULT_WIDTH: usize = 7;
+
+/// Rows are bitmasks, so chambers can't be any wider than this.
+pub const MAX_WIDTH: usize = u8::BITS as usize;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rock {
+    // bit x of each row set if filled, x -> right, y -> top
     // starting with bottom left corner
-    occupied: Vec<Vec<bool>>,
+    rows: Vec<u8>,
+    width: usize,
+}
+
+impl FromStr for Rock {
+    type Err = anyhow::Error;
+
+    /// Parses a rock drawn like in the puzzle, `#` for rock and `.` for air, top row first.
+    fn from_str(s: &str) -> Result<Self> {
+        let rows = s
+            .lines()
+            .rev()
+            .map(|line| {
+                line.chars()
+                    .map(|c| match c {
+                        '#' => Ok(true),
+                        '.' => Ok(false),
+                        _ => Err(anyhow!("Unrecognized rock cell: {c}")),
+                    })
+                    .collect::<Result<Vec<_>>>()
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Rock::new(rows)
+    }
 }
 
 impl Rock {
-    fn falling_rocks_iter_cycle() -> Cycle<Enumerate<IntoIter<&'static Rock, 5>>> {
-        let x = [
-            LazyAccess::get(&ROCK1),
-            LazyAccess::get(&ROCK2),
-            LazyAccess::get(&ROCK3),
-            LazyAccess::get(&ROCK4),
-            LazyAccess::get(&ROCK5),
-        ]
-        .into_iter()
-        .enumerate()
-        .cycle();
-        x
-    }
-
-    fn len(&self) -> usize {
-        self.occupied.iter().map(|row| row.len()).max().unwrap()
+    /// Creates a rock from its rows, bottom row first. Shorter rows are padded with air.
+    pub fn new(occupied: Vec<Vec<bool>>) -> Result<Self> {
+        if !occupied.iter().flatten().any(|&x| x) {
+            return Err(anyhow!("A rock needs at least one occupied cell"));
+        }
+
+        let width = occupied.iter().map(|row| row.len()).max().unwrap_or(0);
+        if width > MAX_WIDTH {
+            return Err(anyhow!(
+                "Rock {width} units wide doesn't fit into any chamber"
+            ));
+        }
+
+        let rows = occupied
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .enumerate()
+                    .filter(|(_, &occupied)| occupied)
+                    .fold(0, |mask, (x, _)| mask | 1 << x)
+            })
+            .collect();
+        Ok(Rock { rows, width })
+    }
+
+    pub fn default_rocks() -> Vec<Rock> {
+        parse_rocks(DEFAULT_ROCKS).unwrap()
     }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Occupied cells as `(x, y)`, relative to the bottom left corner.
+    pub fn cells(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.rows
+            .iter()
+            .enumerate()
+            .flat_map(|(y, &row)| row_cells(row).map(move |x| (x, y)))
+    }
+}
+
+fn row_cells(row: u8) -> impl Iterator<Item = usize> {
+    (0..MAX_WIDTH).filter(move |x| row & 1 << x != 0)
 }
 
-struct Chamber {
-    occupied: Vec<Vec<bool>>,
-    unseen_height: i64,
+/// Parses rocks drawn like in the puzzle, separated by empty lines.
+pub fn parse_rocks(input: &str) -> Result<Vec<Rock>> {
+    input
+        .split("\n\n")
+        .filter(|rock| !rock.trim().is_empty())
+        .map(|rock| rock.trim().parse())
+        .collect()
+}
+
+#[derive(Debug, Clone)]
+pub struct Chamber {
+    width: usize,
+    occupied: Vec<u8>,
 }
 
 impl fmt::Display for Chamber {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        for row in self.occupied.iter().rev() {
-            for &x in row.iter() {
-                if x {
+        for &row in self.occupied.iter().rev() {
+            for x in 0..self.width {
+                if row & 1 << x != 0 {
                     write!(f, "██")?;
                 } else {
                     write!(f, "░░")?;
                 }
             }
-            writeln!(f, "")?;
+            writeln!(f)?;
         }
 
         Ok(())
@@ -93,35 +142,98 @@ impl fmt::Display for Chamber {
 }
 
 impl Chamber {
-    fn is_colliding(&self, rock_state: &RockState) -> bool {
-        if (rock_state.pos.1 + self.occupied.len() as i32) < 0 {
-            return true;
+    fn new(width: usize) -> Self {
+        Chamber {
+            width,
+            occupied: vec![],
         }
-        rock_state
-            .rock
-            .occupied
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Height of the tower of settled rocks.
+    pub fn height(&self) -> i64 {
+        self.occupied.len() as i64
+    }
+
+    /// Whether a settled rock occupies the cell `x` units from the left wall and `y`
+    /// units above the floor.
+    pub fn is_occupied(&self, x: usize, y: i64) -> bool {
+        x < self.width
+            && usize::try_from(y)
+                .ok()
+                .and_then(|y| self.occupied.get(y))
+                .map_or(false, |row| row & 1 << x != 0)
+    }
+
+    /// Cells occupied by settled rocks as `(x, y)`, from the floor up.
+    pub fn occupied_cells(&self) -> impl Iterator<Item = (usize, i64)> + '_ {
+        self.occupied
             .iter()
             .enumerate()
-            .map(|(i, row)| (i as i32 + rock_state.pos.1, row))
-            .filter(|(y, _)| y < &0)
-            .flat_map(|(y, row)| {
-                row.iter().enumerate().filter_map(move |(j, occupied)| {
-                    occupied.then(|| {
-                        let x = j as i32 + rock_state.pos.0;
-                        let i_index: usize = (self.occupied.len() as i32 + y).try_into().ok()?;
-                        let j_index: usize = x.try_into().ok()?;
-                        let r = self.occupied.get(i_index)?.get(j_index)?;
-                        r.then_some(())
-                    })?
-                })
-            })
-            .next()
-            .is_some()
+            .flat_map(|(y, &row)| row_cells(row).map(move |x| (x, y as i64)))
+    }
+
+    fn full_row(&self) -> u8 {
+        (u16::MAX >> (16 - self.width)) as u8
+    }
+
+    /// Air cells a falling rock can still get to, row by row from the top of the tower
+    /// down. Rocks only move sideways and down, so nothing below these ever changes and
+    /// two chambers with the same surface behave the same from then on.
+    fn reachable_surface(&self) -> Vec<u8> {
+        let full = self.full_row();
+        let mut surface = vec![];
+        let mut above = full;
+
+        for &row in self.occupied.iter().rev() {
+            let air = !row & full;
+            let mut reachable = above & air;
+            loop {
+                let spread = (reachable | reachable << 1 | reachable >> 1) & air;
+                if spread == reachable {
+                    break;
+                }
+                reachable = spread;
+            }
+
+            if reachable == 0 {
+                break;
+            }
+            surface.push(reachable);
+            above = reachable;
+        }
+
+        surface
+    }
+
+    fn is_colliding(&self, rock: &Rock, pos: (i32, i64)) -> bool {
+        if pos.0 < 0 || pos.0 as usize + rock.width > self.width || pos.1 < 0 {
+            return true;
+        }
+
+        rock.rows.iter().enumerate().any(|(i, &row)| {
+            self.occupied
+                .get(pos.1 as usize + i)
+                .map_or(false, |&occupied| occupied & row << pos.0 != 0)
+        })
+    }
+
+    fn add(&mut self, rock: &Rock, pos: (i32, i64)) {
+        let top = pos.1 as usize + rock.rows.len();
+        if top > self.occupied.len() {
+            self.occupied.resize(top, 0);
+        }
+        for (i, &row) in rock.rows.iter().enumerate() {
+            self.occupied[pos.1 as usize + i] |= row << pos.0;
+        }
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-enum JetDirection {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JetDirection {
     Left,
     Right,
 }
@@ -138,201 +250,455 @@ impl TryFrom<char> for JetDirection {
     }
 }
 
-struct CycleDescription {
-    rocks_in_between: i64,
-    height_in_between: i64,
+/// What a single [`FallSimulation::step`] did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    /// A new rock appeared three units above the tower.
+    Spawned,
+    /// A jet pushed the falling rock, unless something was in the way.
+    Pushed {
+        direction: JetDirection,
+        blocked: bool,
+    },
+    /// The falling rock moved down one unit.
+    Fell,
+    /// The falling rock hit something below it and came to rest.
+    Settled,
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Step::Spawned => write!(f, "A new rock begins falling"),
+            Step::Pushed { direction, blocked } => {
+                let direction = match direction {
+                    JetDirection::Left => "left",
+                    JetDirection::Right => "right",
+                };
+                if *blocked {
+                    write!(f, "Jet of gas pushes rock {direction}, but nothing happens")
+                } else {
+                    write!(f, "Jet of gas pushes rock {direction}")
+                }
+            }
+            Step::Fell => write!(f, "Rock falls 1 unit"),
+            Step::Settled => write!(f, "Rock falls 1 unit, causing it to come to rest"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct RockState {
+    rock: usize,
+    // bottom left corner, y counted from the floor
+    pos: (i32, i64),
+}
+
+impl RockState {
+    fn moved(&self, delta: (i32, i64)) -> Self {
+        RockState {
+            rock: self.rock,
+            pos: (self.pos.0 + delta.0, self.pos.1 + delta.1),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Blowing(RockState),
+    Falling(RockState),
+    NewRock,
 }
 
-struct FallSimulation {
+/// Rocks falling into a chamber, pushed around by jets of hot gas. Can be run a
+/// state transition, a jet or a rock at a time.
+#[derive(Debug, Clone)]
+pub struct FallSimulation {
     chamber: Chamber,
-    rocks_fallen: i64,
-    max_rocks: i64,
-    rock_iterator: Peekable<Cycle<Enumerate<IntoIter<&'static Rock, 5>>>>,
-    jet_iterator: Peekable<Cycle<Enumerate<std::vec::IntoIter<JetDirection>>>>,
-    tops: HashMap<(usize, usize, Vec<Vec<bool>>), (i64, i64)>,
+    rocks: Vec<Rock>,
+    jets: Vec<JetDirection>,
+    next_rock: usize,
+    next_jet: usize,
+    rocks_settled: i64,
+    state: State,
 }
 
 impl FallSimulation {
-    fn new(max_rocks: i64, jet_directions: Vec<JetDirection>) -> FallSimulation {
-        let chamber = Chamber {
-            occupied: vec![],
-            unseen_height: 0,
-        };
-        let rocks_fallen = 0;
-        let rock_iterator = Rock::falling_rocks_iter_cycle().peekable();
-        let jet_iterator = jet_directions.into_iter().enumerate().cycle().peekable();
-
-        FallSimulation {
-            chamber,
-            rocks_fallen,
-            max_rocks,
-            rock_iterator,
-            jet_iterator,
-            tops: HashMap::new(),
-        }
+    /// Simulation of the puzzle's rocks in a chamber 7 units wide.
+    pub fn new(jets: Vec<JetDirection>) -> Result<Self> {
+        Self::with_config(jets, Rock::default_rocks(), DEFAULT_WIDTH)
     }
 
-    // returns some if it's a cycle
-    fn add_fallen(&mut self, rock_state: RockState) -> Option<CycleDescription> {
-        self.rocks_fallen += 1;
+    /// Simulation of `rocks` falling in turn into a chamber `width` units wide. Every
+    /// rock has to fit in the chamber when it appears, two units away from the left wall.
+    pub fn with_config(jets: Vec<JetDirection>, rocks: Vec<Rock>, width: usize) -> Result<Self> {
+        if jets.is_empty() {
+            return Err(anyhow!("Missing jet directions"));
+        }
+        if rocks.is_empty() {
+            return Err(anyhow!("Missing rocks"));
+        }
+        if width > MAX_WIDTH {
+            return Err(anyhow!(
+                "Chamber {width} units wide is wider than the maximum of {MAX_WIDTH}"
+            ));
+        }
+        if let Some(rock) = rocks.iter().find(|rock| rock.width() + 2 > width) {
+            return Err(anyhow!(
+                "Rock {} units wide doesn't fit into a chamber {width} units wide",
+                rock.width()
+            ));
+        }
 
-        let mut modified = vec![];
+        Ok(FallSimulation {
+            chamber: Chamber::new(width),
+            rocks,
+            jets,
+            next_rock: 0,
+            next_jet: 0,
+            rocks_settled: 0,
+            state: State::NewRock,
+        })
+    }
 
-        for (i, row) in rock_state.rock.occupied.iter().enumerate() {
-            let y = i as i32 + rock_state.pos.1;
+    pub fn chamber(&self) -> &Chamber {
+        &self.chamber
+    }
 
-            if y < 0 {
-                for (j, occupied) in row.iter().enumerate().filter(|(_, occupied)| **occupied) {
-                    let x = j as i32 + rock_state.pos.0;
-                    let i_index: usize =
-                        (self.chamber.occupied.len() as i32 + y).try_into().unwrap();
-                    let j_index: usize = x.try_into().unwrap();
+    /// Height of the tower of settled rocks.
+    pub fn height(&self) -> i64 {
+        self.chamber.height()
+    }
 
-                    modified.push(j_index);
+    pub fn rocks_settled(&self) -> i64 {
+        self.rocks_settled
+    }
 
-                    *self
-                        .chamber
-                        .occupied
-                        .get_mut(i_index)
-                        .unwrap()
-                        .get_mut(j_index)
-                        .unwrap() = *occupied;
-                }
-            } else {
-                let new_row = (0..7)
-                    .map(|i| i as i32 - rock_state.pos.0)
-                    .map(|i| {
-                        (i >= 0)
-                            .then_some(())
-                            .and(row.get(i as usize).copied())
-                            .unwrap_or(false)
-                    })
-                    .collect::<Vec<_>>();
+    /// Index of the rock that appears next.
+    pub fn next_rock(&self) -> usize {
+        self.next_rock
+    }
 
-                self.chamber.occupied.push(new_row);
-            }
-        }
+    /// Index of the jet that pushes next.
+    pub fn next_jet(&self) -> usize {
+        self.next_jet
+    }
 
-        let i_jet = self.jet_iterator.peek().unwrap().0;
-        let i_rock = self.rock_iterator.peek().unwrap().0;
+    /// Cells occupied by the falling rock as `(x, y)`, if there's one.
+    pub fn falling_cells(&self) -> Vec<(usize, i64)> {
+        match &self.state {
+            State::Blowing(state) | State::Falling(state) => self.rocks[state.rock]
+                .cells()
+                .map(|(x, y)| ((state.pos.0 + x as i32) as usize, state.pos.1 + y as i64))
+                .collect(),
+            State::NewRock => vec![],
+        }
+    }
 
-        let last_four = self
-            .chamber
-            .occupied
+    /// Draws the top `window_height` rows of the chamber like in the puzzle, `@` for the
+    /// falling rock and `#` for settled ones, with the floor once it's in view.
+    pub fn render(&self, window_height: usize) -> String {
+        let falling = self.falling_cells();
+        let top = falling
             .iter()
-            .rev()
-            .take(8)
-            .rev()
-            .cloned()
-            .collect::<Vec<_>>();
+            .map(|(_, y)| y + 1)
+            .max()
+            .unwrap_or(0)
+            .max(self.chamber.height());
+        let bottom = (top - window_height as i64).max(0);
 
-        let height_now = self.chamber.unseen_height + self.chamber.occupied.len() as i64;
-
-        if let Some((old_rocks, old_height)) = self
-            .tops
-            .insert((i_jet, i_rock, last_four), (self.rocks_fallen, height_now))
-        {
-            Some(CycleDescription {
-                rocks_in_between: self.rocks_fallen - old_rocks,
-                height_in_between: height_now - old_height,
+        let mut lines = (bottom..top)
+            .rev()
+            .map(|y| {
+                let row = (0..self.chamber.width)
+                    .map(|x| {
+                        if falling.contains(&(x, y)) {
+                            '@'
+                        } else if self.chamber.is_occupied(x, y) {
+                            '#'
+                        } else {
+                            '.'
+                        }
+                    })
+                    .collect::<String>();
+                format!("|{row}|")
             })
-        } else {
-            None
+            .collect::<Vec<_>>();
+        if bottom == 0 {
+            lines.push(format!("+{}+", "-".repeat(self.chamber.width)));
         }
+
+        lines.join("\n")
     }
 
-    fn progress(&mut self, state: State) -> State {
-        match state {
-            State::Blowing(state) => match self.jet_iterator.next() {
-                Some((_, direction)) => {
-                    let new_state = match direction {
-                        JetDirection::Left => state.moved((-1, 0)).unwrap_or(state),
-                        JetDirection::Right => state.moved((1, 0)).unwrap_or(state),
-                    };
-                    if self.chamber.is_colliding(&new_state) {
-                        State::Falling(state)
-                    } else {
-                        State::Falling(new_state)
-                    }
-                }
-                None => State::Falling(state),
-            },
+    /// Runs a single state transition: a rock appearing, a jet push or a fall.
+    pub fn step(&mut self) -> Step {
+        match self.state {
+            State::NewRock => {
+                self.state = State::Blowing(RockState {
+                    rock: self.next_rock,
+                    pos: (2, self.chamber.height() + 3),
+                });
+                self.next_rock = (self.next_rock + 1) % self.rocks.len();
+                Step::Spawned
+            }
+            State::Blowing(state) => {
+                let direction = self.jets[self.next_jet];
+                self.next_jet = (self.next_jet + 1) % self.jets.len();
+
+                let new_state = match direction {
+                    JetDirection::Left => state.moved((-1, 0)),
+                    JetDirection::Right => state.moved((1, 0)),
+                };
+                let blocked = self
+                    .chamber
+                    .is_colliding(&self.rocks[state.rock], new_state.pos);
+                self.state = State::Falling(if blocked { state } else { new_state });
+                Step::Pushed { direction, blocked }
+            }
             State::Falling(state) => {
-                let new_state = state.moved((0, -1)).unwrap();
-                if self.chamber.is_colliding(&new_state) {
-                    if let Some(CycleDescription {
-                        rocks_in_between,
-                        height_in_between,
-                    }) = self.add_fallen(state)
-                    {
-                        let i_cycles =
-                            (self.max_rocks - self.rocks_fallen).div_floor(rocks_in_between);
-                        self.rocks_fallen += i_cycles * rocks_in_between;
-                        self.chamber.unseen_height += i_cycles * height_in_between;
-                    }
-                    State::NewRock
+                let new_state = state.moved((0, -1));
+                if self
+                    .chamber
+                    .is_colliding(&self.rocks[state.rock], new_state.pos)
+                {
+                    self.chamber.add(&self.rocks[state.rock], state.pos);
+                    self.rocks_settled += 1;
+                    self.state = State::NewRock;
+                    Step::Settled
                 } else {
-                    State::Blowing(new_state)
+                    self.state = State::Blowing(new_state);
+                    Step::Fell
                 }
             }
-            State::NewRock => {
-                if self.rocks_fallen == self.max_rocks {
-                    State::End
-                } else {
-                    State::Blowing(RockState {
-                        pos: (2, 3),
-                        rock: self.rock_iterator.next().unwrap().1,
-                    })
-                }
+        }
+    }
+
+    /// Runs until the next jet has pushed and the rock has fallen (or settled) after it.
+    pub fn step_jet(&mut self) -> Step {
+        loop {
+            match self.step() {
+                step @ (Step::Fell | Step::Settled) => return step,
+                Step::Spawned | Step::Pushed { .. } => {}
             }
-            State::End => State::End,
         }
     }
+
+    /// Runs until the falling rock, or the next one if none is falling, settles.
+    pub fn step_rock(&mut self) {
+        while self.step() != Step::Settled {}
+    }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-struct RockState {
-    pos: (i32, i32),
-    rock: &'static Rock,
+/// The tower grows the same way over and over once the jets, the rocks and the surface
+/// of the tower line up with an earlier point of the simulation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CycleReport {
+    /// Rocks settled before the first cycle starts.
+    pub start: i64,
+    /// Rocks settling in every cycle.
+    pub length: i64,
+    /// Height the tower gains in every cycle.
+    pub height_gain: i64,
+    // heights[n]: height after n rocks, up to the end of the first cycle
+    heights: Vec<i64>,
 }
 
-impl RockState {
-    fn moved(&self, delta: (i32, i32)) -> Option<Self> {
-        let RockState { pos, rock } = self;
-        let new_x = pos.0 + delta.0;
-        (new_x >= 0 && new_x + (self.rock.len() as i32) <= 7).then(|| ())?;
-        let new_y = pos.1 + delta.1;
-        Some(RockState {
-            pos: (new_x, new_y),
-            rock,
-        })
+impl fmt::Display for CycleReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Cycle of {} rocks starting after rock {}, the tower grows by {} every cycle",
+            self.length, self.start, self.height_gain
+        )
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-enum State {
-    Blowing(RockState),
-    Falling(RockState),
-    NewRock,
-    End,
+impl CycleReport {
+    /// Runs a simulation until the state after a rock settles repeats, giving up after
+    /// `max_rocks` rocks. Heights are counted from an empty chamber, so there's no
+    /// report for a simulation that already has rocks settled in it.
+    pub fn find(mut simulation: FallSimulation, max_rocks: i64) -> Option<Self> {
+        if simulation.rocks_settled() != 0 {
+            return None;
+        }
+
+        let mut seen = HashMap::new();
+        let mut heights = vec![simulation.height()];
+
+        while simulation.rocks_settled() < max_rocks {
+            simulation.step_rock();
+            heights.push(simulation.height());
+
+            let key = (
+                simulation.next_jet(),
+                simulation.next_rock(),
+                simulation.chamber().reachable_surface(),
+            );
+            if let Some(start) = seen.insert(key, simulation.rocks_settled()) {
+                let end = simulation.rocks_settled();
+                return Some(CycleReport {
+                    start,
+                    length: end - start,
+                    height_gain: heights[end as usize] - heights[start as usize],
+                    heights,
+                });
+            }
+        }
+
+        None
+    }
+
+    /// Height of the tower after `num_rocks` rocks.
+    pub fn height_after(&self, num_rocks: i64) -> i64 {
+        if num_rocks < self.start {
+            return self.heights[num_rocks as usize];
+        }
+
+        let cycles = (num_rocks - self.start) / self.length;
+        let offset = (num_rocks - self.start) % self.length;
+        self.heights[(self.start + offset) as usize] + cycles * self.height_gain
+    }
+
+    /// Checks the predicted heights against a brute-force run of `simulation` for every
+    /// number of rocks up to `max_rocks`. Like [`CycleReport::find`], it has to start out
+    /// with no rocks settled.
+    pub fn verify(&self, mut simulation: FallSimulation, max_rocks: i64) -> Result<()> {
+        if simulation.rocks_settled() != 0 {
+            return Err(anyhow!(
+                "Can't verify against a simulation with {} rocks already settled",
+                simulation.rocks_settled()
+            ));
+        }
+
+        while simulation.rocks_settled() < max_rocks {
+            simulation.step_rock();
+
+            let rocks = simulation.rocks_settled();
+            let predicted = self.height_after(rocks);
+            if predicted != simulation.height() {
+                return Err(anyhow!(
+                    "Predicted height {predicted} after {rocks} rocks, the simulation reached {}",
+                    simulation.height()
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Frames of a simulation, one before anything happens and one after every step until
+/// `num_rocks` rocks have settled.
+pub struct Replay {
+    simulation: FallSimulation,
+    num_rocks: i64,
+    window_height: usize,
+    started: bool,
+}
+
+impl Replay {
+    pub fn new(simulation: FallSimulation, num_rocks: i64, window_height: usize) -> Self {
+        Replay {
+            simulation,
+            num_rocks,
+            window_height,
+            started: false,
+        }
+    }
 }
 
-pub fn get_tower_height(mut input: impl Iterator<Item = String>, num_rocks: i64) -> Result<i64> {
-    let jet_directions = input
+impl Iterator for Replay {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let description = if !self.started {
+            self.started = true;
+            "The chamber is empty".to_string()
+        } else if self.simulation.rocks_settled() < self.num_rocks {
+            self.simulation.step().to_string()
+        } else {
+            return None;
+        };
+
+        Some(format!(
+            "Rocks settled: {}, height: {}\n{description}:\n{}",
+            self.simulation.rocks_settled(),
+            self.simulation.height(),
+            self.simulation.render(self.window_height)
+        ))
+    }
+}
+
+fn parse_jet_directions(mut input: impl Iterator<Item = String>) -> Result<Vec<JetDirection>> {
+    input
         .next()
         .ok_or(anyhow!["Missing jet directions input!"])?
         .chars()
         .map(TryFrom::try_from)
-        .collect::<Result<Vec<JetDirection>>>()?;
+        .collect::<Result<Vec<JetDirection>>>()
+}
+
+fn tower_height(jet_directions: &[JetDirection], num_rocks: i64) -> Result<i64> {
+    let simulation = FallSimulation::new(jet_directions.to_vec())?;
+    if let Some(report) = CycleReport::find(simulation.clone(), num_rocks) {
+        return Ok(report.height_after(num_rocks));
+    }
+
+    let mut simulation = simulation;
+    while simulation.rocks_settled() < num_rocks {
+        simulation.step_rock();
+    }
+    Ok(simulation.height())
+}
+
+pub fn get_tower_height(input: impl Iterator<Item = String>, num_rocks: i64) -> Result<i64> {
+    tower_height(&parse_jet_directions(input)?, num_rocks)
+}
+
+/// Finds the cycle in the first `max_rocks` rocks and, if `verify_rocks` is given,
+/// checks it against a brute-force simulation of that many rocks.
+pub fn get_cycle_report(
+    input: impl Iterator<Item = String>,
+    max_rocks: i64,
+    verify_rocks: Option<i64>,
+) -> Result<CycleReport> {
+    let simulation = FallSimulation::new(parse_jet_directions(input)?)?;
+    let report = CycleReport::find(simulation.clone(), max_rocks)
+        .ok_or_else(|| anyhow!("No cycle in the first {max_rocks} rocks"))?;
+    if let Some(verify_rocks) = verify_rocks {
+        report.verify(simulation, verify_rocks)?;
+    }
+    Ok(report)
+}
+
+pub fn get_replay(
+    input: impl Iterator<Item = String>,
+    num_rocks: i64,
+    window_height: usize,
+) -> Result<Replay> {
+    let simulation = FallSimulation::new(parse_jet_directions(input)?)?;
+    Ok(Replay::new(simulation, num_rocks, window_height))
+}
+
+pub struct Day17;
 
-    let mut fall_simulation = FallSimulation::new(num_rocks, jet_directions);
+impl Solver for Day17 {
+    type Input = Vec<JetDirection>;
+    type Answer1 = i64;
+    type Answer2 = i64;
 
-    let mut state = State::NewRock;
-    while state != State::End {
-        state = fall_simulation.progress(state);
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_jet_directions(input.lines().map(|l| l.to_string()))
     }
 
-    Ok(fall_simulation.chamber.occupied.len() as i64 + fall_simulation.chamber.unseen_height)
+    fn part1(jet_directions: &Self::Input) -> Result<Self::Answer1> {
+        tower_height(jet_directions, 2022)
+    }
+
+    fn part2(jet_directions: &Self::Input) -> Result<Self::Answer2> {
+        tower_height(jet_directions, 1000000000000)
+    }
 }
 
 #[cfg(test)]
@@ -354,4 +720,148 @@ mod tests {
         assert!(res.is_ok());
         assert_eq!(res.unwrap(), 1514285714288);
     }
+
+    #[test]
+    fn steps() {
+        let jets = Day17::parse(TEST_INPUT).unwrap();
+        let mut simulation = FallSimulation::new(jets).unwrap();
+
+        let steps = (0..9).map(|_| simulation.step()).collect::<Vec<_>>();
+        assert_eq!(
+            steps,
+            vec![
+                Step::Spawned,
+                Step::Pushed {
+                    direction: JetDirection::Right,
+                    blocked: false
+                },
+                Step::Fell,
+                Step::Pushed {
+                    direction: JetDirection::Right,
+                    blocked: true
+                },
+                Step::Fell,
+                Step::Pushed {
+                    direction: JetDirection::Right,
+                    blocked: true
+                },
+                Step::Fell,
+                Step::Pushed {
+                    direction: JetDirection::Left,
+                    blocked: false
+                },
+                Step::Settled,
+            ]
+        );
+        assert_eq!(simulation.height(), 1);
+        assert_eq!(
+            simulation.chamber().occupied_cells().collect::<Vec<_>>(),
+            vec![(2, 0), (3, 0), (4, 0), (5, 0)]
+        );
+
+        assert_eq!(simulation.step_jet(), Step::Fell);
+        assert_eq!(
+            simulation.falling_cells(),
+            vec![(2, 3), (1, 4), (2, 4), (3, 4), (2, 5)]
+        );
+
+        simulation.step_rock();
+        for _ in 2..2022 {
+            simulation.step_rock();
+        }
+        assert_eq!(simulation.rocks_settled(), 2022);
+        assert_eq!(simulation.height(), 3068);
+    }
+
+    #[test]
+    fn custom_config() {
+        let rocks = parse_rocks("#").unwrap();
+        let jets = vec![JetDirection::Left];
+        let mut simulation = FallSimulation::with_config(jets.clone(), rocks, 3).unwrap();
+        for _ in 0..5 {
+            simulation.step_rock();
+        }
+        assert_eq!(simulation.height(), 5);
+        assert!((0..5).all(|y| simulation.chamber().is_occupied(0, y)));
+        assert_eq!(simulation.chamber().occupied_cells().count(), 5);
+
+        let rocks = parse_rocks("####\n\n.#\n##").unwrap();
+        assert_eq!(
+            rocks[1].cells().collect::<Vec<_>>(),
+            vec![(0, 0), (1, 0), (1, 1)]
+        );
+        assert!(FallSimulation::with_config(jets, rocks, 5).is_err());
+    }
+
+    #[test]
+    fn cycle() {
+        let res = get_cycle_report(TEST_INPUT.lines().map(|l| l.to_string()), 10000, Some(5000));
+        assert!(res.is_ok());
+        let report = res.unwrap();
+        assert_eq!(report.length, 35);
+        assert_eq!(report.height_gain, 53);
+        assert_eq!(report.height_after(2022), 3068);
+        assert_eq!(report.height_after(1000000000000), 1514285714288);
+
+        let wrong = CycleReport {
+            height_gain: report.height_gain + 1,
+            ..report.clone()
+        };
+        let simulation = FallSimulation::new(Day17::parse(TEST_INPUT).unwrap()).unwrap();
+        assert!(wrong.verify(simulation.clone(), 5000).is_err());
+
+        // Heights are counted from an empty chamber
+        let mut stepped = simulation;
+        stepped.step_rock();
+        assert!(CycleReport::find(stepped.clone(), 10000).is_none());
+        assert!(report.verify(stepped, 5000).is_err());
+    }
+
+    #[test]
+    fn reachable_surface() {
+        // The air under the overhang on the left can't be reached from above, the floor
+        // still can be by going down on the right
+        let mut chamber = Chamber::new(4);
+        chamber.add(&"##.\n..#".parse().unwrap(), (0, 1));
+        assert_eq!(chamber.reachable_surface(), vec![0b1100, 0b1000, 0b1111]);
+    }
+
+    #[test]
+    fn replay() {
+        let res = get_replay(TEST_INPUT.lines().map(|l| l.to_string()), 2, 10);
+        assert!(res.is_ok());
+        let frames = res.unwrap().collect::<Vec<_>>();
+        assert_eq!(
+            frames[1],
+            "\
+Rocks settled: 0, height: 0
+A new rock begins falling:
+|..@@@@.|
+|.......|
+|.......|
+|.......|
++-------+"
+        );
+        assert_eq!(
+            frames[4],
+            "\
+Rocks settled: 0, height: 0
+Jet of gas pushes rock right, but nothing happens:
+|...@@@@|
+|.......|
+|.......|
++-------+"
+        );
+        assert_eq!(
+            frames.last().unwrap(),
+            "\
+Rocks settled: 2, height: 4
+Rock falls 1 unit, causing it to come to rest:
+|...#...|
+|..###..|
+|...#...|
+|..####.|
++-------+"
+        );
+    }
 }
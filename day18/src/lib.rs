@@ -5,6 +5,7 @@ use std::collections::{BTreeSet, HashMap, HashSet};
 
 use anyhow::{anyhow, Result};
 use itertools::iproduct;
+use util::Solver;
 
 static DIRECTIONS: [(i32, i32, i32); 6] = [
     (-1, 0, 0),
@@ -15,13 +16,11 @@ static DIRECTIONS: [(i32, i32, i32); 6] = [
     (0, 0, 1),
 ];
 
-pub fn get_num_exposed_sides(input: impl Iterator<Item = String>) -> Result<u64> {
+fn num_exposed_sides(locations: &[(i32, i32, i32)]) -> u64 {
     let mut exposed_sides = 0;
     let mut cubes: HashSet<(i32, i32, i32)> = HashSet::new();
 
-    for loc in input.map(parse_location) {
-        let loc = loc?;
-
+    for &loc in locations {
         let neighbours = DIRECTIONS
             .iter()
             .filter_map(|d| {
@@ -35,11 +34,15 @@ pub fn get_num_exposed_sides(input: impl Iterator<Item = String>) -> Result<u64>
         cubes.insert(loc);
     }
 
-    Ok(exposed_sides)
+    exposed_sides
 }
 
-pub fn get_num_exposed_sides_2(input: impl Iterator<Item = String>, only_out: bool) -> Result<i64> {
-    let cubes = input.map(parse_location).collect::<Result<HashSet<_>>>()?;
+pub fn get_num_exposed_sides(input: impl Iterator<Item = String>) -> Result<u64> {
+    Ok(num_exposed_sides(&parse_locations(input)?))
+}
+
+fn num_exposed_sides_2(locations: &[(i32, i32, i32)], only_out: bool) -> Result<i64> {
+    let cubes = locations.iter().copied().collect::<HashSet<_>>();
 
     let ranges = cubes
         .iter()
@@ -107,6 +110,14 @@ pub fn get_num_exposed_sides_2(input: impl Iterator<Item = String>, only_out: bo
     Ok(exposed_sides)
 }
 
+pub fn get_num_exposed_sides_2(input: impl Iterator<Item = String>, only_out: bool) -> Result<i64> {
+    num_exposed_sides_2(&parse_locations(input)?, only_out)
+}
+
+fn parse_locations(input: impl Iterator<Item = String>) -> Result<Vec<(i32, i32, i32)>> {
+    input.map(parse_location).collect()
+}
+
 fn parse_location(l: String) -> Result<(i32, i32, i32)> {
     let v: [i32; 3] = l
         .split(',')
@@ -117,6 +128,26 @@ fn parse_location(l: String) -> Result<(i32, i32, i32)> {
     Ok((v[0], v[1], v[2]))
 }
 
+pub struct Day18;
+
+impl Solver for Day18 {
+    type Input = Vec<(i32, i32, i32)>;
+    type Answer1 = u64;
+    type Answer2 = i64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_locations(input.lines().map(|l| l.to_string()))
+    }
+
+    fn part1(locations: &Self::Input) -> Result<Self::Answer1> {
+        Ok(num_exposed_sides(locations))
+    }
+
+    fn part2(locations: &Self::Input) -> Result<Self::Answer2> {
+        num_exposed_sides_2(locations, true)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,12 @@
+use day19::get_max_geodes_product;
+use util::read_input_as_string;
+
+use anyhow::Result;
+
+fn main() -> Result<()> {
+    let res = get_max_geodes_product(read_input_as_string()?.as_str())?;
+
+    println!("{res}");
+
+    Ok(())
+}
@@ -0,0 +1,25 @@
+use std::env;
+
+use anyhow::{Context, Result};
+use day19::get_schedules;
+use util::read_input_as_string;
+
+fn main() -> Result<()> {
+    let minutes = match env::args().nth(1) {
+        Some(minutes) => minutes.parse().context("Minutes must be a number")?,
+        None => 24,
+    };
+
+    let schedules = get_schedules(read_input_as_string()?.as_str(), minutes, |p| {
+        eprintln!(
+            "[{}/{}] blueprint {} collects {}",
+            p.done, p.total, p.id, p.collected
+        )
+    })?;
+
+    for (id, schedule) in schedules {
+        println!("Blueprint {id}:\n{schedule}\n");
+    }
+
+    Ok(())
+}
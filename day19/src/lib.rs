@@ -1,9 +1,9 @@
 #![feature(try_blocks)]
 #![feature(iter_intersperse)]
 
-use std::cmp::Ordering;
-use std::collections::{BTreeMap, BTreeSet};
-use std::str::FromStr;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::atomic::{self, AtomicUsize};
 
 use anyhow::{anyhow, Result};
 use itertools::Itertools;
@@ -12,314 +12,333 @@ use nom::multi::separated_list0;
 use nom::sequence::{delimited, pair, preceded, separated_pair};
 use nom::{
     bytes::complete::tag,
-    combinator::{map, map_res},
+    combinator::{cut, map, map_res},
     error::ParseError,
     sequence::tuple,
     IResult,
 };
-use num_derive::FromPrimitive;
-use strum::EnumCount;
-use strum_macros::{Display, EnumCount, EnumString};
-use util::{parse_nice, parse_number, Span};
-
-#[derive(
-    Debug,
-    Copy,
-    Clone,
-    Display,
-    PartialEq,
-    Eq,
-    PartialOrd,
-    Ord,
-    EnumString,
-    EnumCount,
-    FromPrimitive,
-)]
-enum Material {
-    #[strum(ascii_case_insensitive)]
-    Ore = 0,
-    #[strum(ascii_case_insensitive)]
-    Clay,
-    #[strum(ascii_case_insensitive)]
-    Obsidian,
-    #[strum(ascii_case_insensitive)]
-    Geode,
-}
-
-impl Material {
-    fn next(&self) -> Self {
-        match *self {
-            Material::Ore => Material::Clay,
-            Material::Clay => Material::Obsidian,
-            Material::Obsidian => Material::Geode,
-            Material::Geode => Material::Geode,
-        }
-    }
-}
+use rayon::prelude::*;
+use util::{parse_input, parse_number, Solver, Span};
 
-static MATERIALS: [Material; Material::COUNT] = [
-    Material::Ore,
-    Material::Clay,
-    Material::Obsidian,
-    Material::Geode,
-];
+/// Material maximised when a blueprint has a robot collecting it, otherwise the one
+/// collected by the last robot listed is.
+pub const DEFAULT_TARGET: &str = "geode";
 
+/// Robot recipes over materials named in the input. Materials are referred to by their
+/// index in `materials`, in order of first appearance. One robot collecting the
+/// material of the first recipe is available from the start.
 #[derive(Debug)]
-struct Blueprint {
-    costs: BTreeMap<Material, BTreeMap<Material, i32>>,
+pub struct Blueprint {
+    materials: Vec<String>,
+    costs: Vec<Option<Vec<(usize, i32)>>>,
+    recipes: Vec<usize>,
+    target: usize,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd)]
-struct State {
-    minute: i32,
-    materials: [i32; Material::COUNT],
-    incomes: [i32; Material::COUNT],
-}
+type Recipe<'a> = (&'a str, Vec<(i64, &'a str)>);
 
-impl Ord for State {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        let calc_score = |s: &State| {
-            (0..4)
-                .map(|i| {
-                    10f32.powf(i as f32 * 3.0)
-                        * (s.materials[i] as f32 + s.incomes[i] as f32 * (24 - s.minute) as f32)
-                })
-                .sum::<f32>()
+impl Blueprint {
+    fn new(recipes: Vec<Recipe>) -> Result<Self> {
+        let mut materials: Vec<String> = vec![];
+        let mut index = |name: &str| match materials.iter().position(|m| m == name) {
+            Some(i) => i,
+            None => {
+                materials.push(name.to_string());
+                materials.len() - 1
+            }
         };
 
-        let self_score = calc_score(self);
-        let other_score = calc_score(other);
-        let cmp = self_score.partial_cmp(&other_score);
-        if let Some(cmp) = cmp {
-            if !cmp.is_eq() {
-                return cmp;
+        let recipes = recipes
+            .into_iter()
+            .map(|(robot, costs)| {
+                let robot = index(robot);
+                let costs = costs
+                    .into_iter()
+                    .map(|(cost, m)| (index(m), cost as i32))
+                    .collect::<Vec<_>>();
+                (robot, costs)
+            })
+            .collect::<Vec<_>>();
+
+        let mut costs = vec![None; materials.len()];
+        for (robot, robot_costs) in recipes.iter() {
+            if costs[*robot].replace(robot_costs.clone()).is_some() {
+                return Err(anyhow!("Robot for {} is listed twice", materials[*robot]));
             }
         }
 
-        (self.minute, self.materials, self.incomes).cmp(&(
-            other.minute,
-            other.materials,
-            other.incomes,
-        ))
+        let order = recipes.iter().map(|(robot, _)| *robot).collect::<Vec<_>>();
+        let target = materials
+            .iter()
+            .position(|m| m == DEFAULT_TARGET)
+            .filter(|i| costs[*i].is_some())
+            .or(order.last().copied())
+            .ok_or(anyhow!("Blueprint has no robots"))?;
+
+        Ok(Blueprint {
+            materials,
+            costs,
+            recipes: order,
+            target,
+        })
+    }
+
+    pub fn materials(&self) -> &[String] {
+        self.materials.as_slice()
     }
+
+    pub fn target(&self) -> &str {
+        self.materials[self.target].as_str()
+    }
+
+    pub fn with_target(mut self, target: &str) -> Result<Self> {
+        self.target = self
+            .materials
+            .iter()
+            .position(|m| m == target)
+            .filter(|i| self.costs[*i].is_some())
+            .ok_or(anyhow!("No robot collects {target}"))?;
+        Ok(self)
+    }
+
+    fn costs(&self, robot: usize) -> &[(usize, i32)] {
+        self.costs[robot].as_deref().unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct State {
+    minute: i32,
+    materials: Vec<i32>,
+    incomes: Vec<i32>,
 }
 
 impl State {
-    fn new() -> Self {
-        let mut incomes = [0; Material::COUNT];
-        incomes[Material::Ore as usize] = 1;
+    fn new(blueprint: &Blueprint) -> Self {
+        let mut incomes = vec![0; blueprint.materials.len()];
+        incomes[blueprint.recipes[0]] = 1;
 
         State {
             minute: 0,
-            materials: [0; Material::COUNT],
+            materials: vec![0; blueprint.materials.len()],
             incomes,
         }
     }
 
-    fn progress(mut self) -> Self {
+    pub fn minute(&self) -> i32 {
+        self.minute
+    }
+
+    /// Amount of each material held, indexed like [`Blueprint::materials`].
+    pub fn materials(&self) -> &[i32] {
+        self.materials.as_slice()
+    }
+
+    /// Number of robots collecting each material, indexed like [`Blueprint::materials`].
+    pub fn incomes(&self) -> &[i32] {
+        self.incomes.as_slice()
+    }
+
+    fn progress(mut self, minutes: i32) -> Self {
         for (inc_m_i, inc) in self.incomes.iter().enumerate() {
-            self.materials[inc_m_i] += inc;
+            self.materials[inc_m_i] += inc * minutes;
         }
 
         State {
-            minute: self.minute + 1,
+            minute: self.minute + minutes,
             materials: self.materials,
             incomes: self.incomes,
         }
     }
 
-    fn build_robot(mut self, blueprint: &Blueprint, material: Material) -> Self {
-        for (cost_m, c) in blueprint.costs[&material].iter() {
-            self.materials[*cost_m as usize] -= c;
+    fn build_robot(mut self, blueprint: &Blueprint, material: usize) -> Self {
+        for (cost_m, c) in blueprint.costs(material).iter() {
+            self.materials[*cost_m] -= c;
         }
 
-        self.incomes[material as usize] += 1;
+        self.incomes[material] += 1;
 
         self
     }
 
-    fn turns_to(&self, blueprint: &Blueprint, material: Material) -> Option<f32> {
-        blueprint.costs[&material]
+    /// Minutes of collecting needed before a `material` robot can be afforded, `None`
+    /// if one of its costs isn't being collected at all.
+    fn minutes_to_afford(&self, blueprint: &Blueprint, material: usize) -> Option<i32> {
+        blueprint
+            .costs(material)
             .iter()
-            .map(|(cost_m, cost)| -> Option<f32> {
-                let income = self.incomes[*cost_m as usize];
-                (income > 0).then(|| {
-                    -(*cost as f32 - self.materials[*cost_m as usize] as f32).max(0.0)
-                        / self.incomes[*cost_m as usize] as f32
-                })
+            .map(|(cost_m, cost)| {
+                let missing = cost - self.materials[*cost_m];
+                let income = self.incomes[*cost_m];
+                if missing <= 0 {
+                    Some(0)
+                } else if income > 0 {
+                    Some((missing + income - 1) / income)
+                } else {
+                    None
+                }
             })
-            .collect::<Option<Vec<_>>>()?
-            .iter()
-            .copied()
-            .sorted_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal))
-            .next()
+            .try_fold(0, |max, minutes| Some(max.max(minutes?)))
     }
 
-    fn is_build_desirable(&self, blueprint: &Blueprint, m: &Material) -> bool {
-        match m {
-            Material::Geode => true,
-            Material::Obsidian | Material::Clay => {
-                (self.incomes[*m as usize] as f32 / self.incomes[Material::Ore as usize] as f32)
-                    < (blueprint.costs[&m.next()][m] as f32
-                        / blueprint.costs[&m.next()][&Material::Ore] as f32)
-            }
-            Material::Ore => true,
+    /// Depth first search over which robot to build next (skipping the minutes spent
+    /// waiting for it), pruning branches that can't beat `best` even if a target robot
+    /// were built every remaining minute. `builds` holds the robots built so far.
+    fn search(
+        self,
+        blueprint: &Blueprint,
+        minutes: i32,
+        max_incomes: &[i32],
+        builds: &mut Vec<usize>,
+        best: &mut (i32, Vec<usize>),
+    ) {
+        let target = blueprint.target;
+        let remaining = minutes - self.minute;
+
+        let idle_collected = self.materials[target] + self.incomes[target] * remaining;
+        if idle_collected > best.0 {
+            *best = (idle_collected, builds.clone());
         }
-    }
 
-    fn get_best_next(states: Vec<State>, blueprint: &Blueprint) -> State {
-        //println!("\n\n\nstart inspect\n\n\n");
-        let mut sorted_by_turns = states
-            .into_iter()
-            .flat_map(|state| {
-                let available = MATERIALS
-                    .iter()
-                    .filter(|m| {
-                        blueprint.costs[m]
-                            .iter()
-                            .all(|(cost_m, cost)| cost <= &state.materials[*cost_m as usize])
-                    })
-                    .map(|m| Some(m))
-                    .chain(std::iter::once(None))
-                    .collect::<BTreeSet<_>>();
-
-                let new_state = state.progress();
-
-                available
-                    .iter()
-                    .map(move |&m_to_build| {
-                        let new_state_considered = if let Some(m_to_build) = m_to_build {
-                            new_state.clone().build_robot(blueprint, *m_to_build)
-                        } else {
-                            new_state.clone()
-                        };
-
-                        (
-                            new_state_considered,
-                            m_to_build,
-                            MATERIALS
-                                .iter()
-                                .rev()
-                                .map(|m| new_state.turns_to(blueprint, *m))
-                                .collect::<Vec<_>>(),
-                        )
-                    })
-                    .collect::<Vec<_>>()
-            })
-            .sorted_by(|(s1, ma, a), (_, mb, b)| {
-                //println!("For minute {} Comparing {a:#?} with {b:#?}", s1.minute);
-                //println!("Result: {:#?}", a.partial_cmp(b));
-                if ma == &Some(&Material::Geode) {
-                    Ordering::Greater
-                } else if mb == &Some(&Material::Geode) {
-                    Ordering::Less
-                } else {
-                    a.partial_cmp(b).unwrap_or(Ordering::Equal)
-                }
-            })
-            .inspect(|(s, m, t)| println!("For state: {s:#?}, built: {m:#?}, turns: {t:#?}\n\n"))
-            .collect::<Vec<_>>();
+        let bound = idle_collected + remaining * (remaining - 1) / 2;
+        if bound <= best.0 {
+            return;
+        }
 
-        //println!("\n\n\nEnd inspect\n\n\n\n\n\n");
+        let robots = std::iter::once(target).chain(
+            blueprint
+                .recipes
+                .iter()
+                .rev()
+                .copied()
+                .filter(|r| *r != target),
+        );
+
+        for robot in robots {
+            if self.incomes[robot] >= max_incomes[robot] {
+                continue;
+            }
 
-        let mut best_states_with_turns: Vec<(State, Option<&Material>, Vec<Option<f32>>)> = vec![];
-        while let Some((state, built_m, turns)) = sorted_by_turns.pop() {
-            if let Some((_, _, best_turns)) = best_states_with_turns.first() {
-                if best_turns == &turns && state.minute < 24 {
-                    best_states_with_turns.push((state, built_m, turns))
+            if let Some(wait) = self.minutes_to_afford(blueprint, robot) {
+                // A robot finished in the last minute never collects anything
+                if wait + 1 < remaining {
+                    builds.push(robot);
+                    self.clone()
+                        .progress(wait + 1)
+                        .build_robot(blueprint, robot)
+                        .search(blueprint, minutes, max_incomes, builds, best);
+                    builds.pop();
                 }
-            } else {
-                best_states_with_turns.push((state, built_m, turns))
             }
         }
+    }
+}
 
-        let solved = if best_states_with_turns.len() == 1 {
-            true
-        } else {
-            if let Some(build_material) = best_states_with_turns.first().unwrap().1 {
-                if build_material == &Material::Geode {
-                    true
-                } else {
-                    false
-                }
-            } else if let Some(turns_to_build_geode) = best_states_with_turns.first().unwrap().2[0]
-            {
-                if turns_to_build_geode > -1.0 {
-                    true
-                } else {
-                    false
-                }
-            } else {
-                false
-            }
-        };
+/// A robot built during `minute`, and the state at the end of that minute.
+#[derive(Debug, Clone)]
+pub struct Build {
+    pub minute: i32,
+    pub robot: String,
+    pub state: State,
+}
 
-        if solved {
-            best_states_with_turns.pop().unwrap().0
-        } else {
-            State::get_best_next(
-                best_states_with_turns
-                    .into_iter()
-                    .map(|(state, _, _)| state)
-                    .collect::<Vec<_>>(),
-                blueprint,
-            )
+/// The build order that collects the most of a blueprint's target material.
+#[derive(Debug, Clone)]
+pub struct Schedule {
+    pub materials: Vec<String>,
+    pub target: String,
+    pub collected: i32,
+    pub builds: Vec<Build>,
+    pub end: State,
+}
+
+impl Schedule {
+    /// Replays building `robots` in order, each as soon as it can be afforded.
+    fn replay(blueprint: &Blueprint, minutes: i32, robots: &[usize]) -> Self {
+        let mut state = State::new(blueprint);
+        let mut builds = vec![];
+
+        for robot in robots {
+            let wait = state
+                .minutes_to_afford(blueprint, *robot)
+                .expect("Scheduled robot can never be afforded");
+            state = state.progress(wait + 1).build_robot(blueprint, *robot);
+            builds.push(Build {
+                minute: state.minute,
+                robot: blueprint.materials[*robot].clone(),
+                state: state.clone(),
+            });
+        }
+
+        let end = state.clone().progress(minutes - state.minute);
+
+        Schedule {
+            materials: blueprint.materials.clone(),
+            target: blueprint.target().to_string(),
+            collected: end.materials[blueprint.target],
+            builds,
+            end,
         }
     }
 
-    fn get_next(&self, blueprint: &Blueprint) -> Vec<State> {
-        let available = MATERIALS.iter().filter(|m| {
-            blueprint.costs[m]
+    fn fmt_state(&self, state: &State) -> String {
+        let list = |amounts: &[i32]| {
+            self.materials
                 .iter()
-                .all(|(cost_m, cost)| cost <= &self.materials[*cost_m as usize])
-        });
-
-        available
-            .map(|m| (Some(m), self.clone()))
-            //            .inspect(|(m, _)| println!("  Available m: {}", m.unwrap()))
-            .chain(std::iter::once((None, self.clone())))
-            .map(|(m, state)| (m, state.progress()))
-            .map(|(m, mut state)| {
-                if let Some(m) = m {
-                    state = state.build_robot(blueprint, *m)
-                }
+                .zip(amounts)
+                .map(|(m, amount)| format!("{amount} {m}"))
+                .join(", ")
+        };
 
-                state
-            })
-            //            .inspect(|s| println!("{s:#?}"))
-            .collect()
+        format!(
+            "materials: {}; robots: {}",
+            list(&state.materials),
+            list(&state.incomes)
+        )
     }
 }
 
-fn parse_material<'a, E>(i: Span<'a>) -> IResult<Span<'a>, Material, E>
-where
-    E: ParseError<Span<'a>> + nom::error::FromExternalError<Span<'a>, anyhow::Error>,
-{
-    map_res(alpha1, |m: Span<'a>| {
-        FromStr::from_str(m.fragment()).map_err(anyhow::Error::msg)
-    })(i)
+impl fmt::Display for Schedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for build in self.builds.iter() {
+            writeln!(
+                f,
+                "Minute {:>2}: build {} robot -> {}",
+                build.minute,
+                build.robot,
+                self.fmt_state(&build.state)
+            )?;
+        }
+        writeln!(
+            f,
+            "Minute {:>2}: end -> {}",
+            self.end.minute,
+            self.fmt_state(&self.end)
+        )?;
+        write!(f, "{} {} collected", self.collected, self.target)
+    }
 }
 
-fn parse_robot_cost<'a, E>(i: Span<'a>) -> IResult<Span<'a>, (Material, BTreeMap<Material, i32>), E>
+fn parse_robot_cost<'a, E>(i: Span<'a>) -> IResult<Span<'a>, Recipe<'a>, E>
 where
     E: ParseError<Span<'a>> + nom::error::FromExternalError<Span<'a>, anyhow::Error>,
 {
     map(
         tuple((
-            preceded(tag("Each "), parse_material),
-            delimited(
+            preceded(tag("Each "), cut(alpha1)),
+            cut(delimited(
                 tag(" robot costs "),
-                separated_list0(
-                    tag(" and "),
-                    separated_pair(parse_number, tag(" "), parse_material),
-                ),
+                separated_list0(tag(" and "), separated_pair(parse_number, tag(" "), alpha1)),
                 tag("."),
-            ),
+            )),
         )),
-        |(m, costs_vec)| {
+        |(m, costs_vec): (Span<'a>, Vec<(i64, Span<'a>)>)| {
             (
-                m,
-                costs_vec.into_iter().map(|(i, m)| (m, i as i32)).collect(),
+                *m.fragment(),
+                costs_vec
+                    .into_iter()
+                    .map(|(i, m)| (i, *m.fragment()))
+                    .collect(),
             )
         },
     )(i)
@@ -332,63 +351,144 @@ where
     map(
         pair(
             delimited(tag("Blueprint "), parse_number, pair(tag(":"), multispace1)),
-            separated_list0(multispace1, parse_robot_cost),
+            cut(map_res(
+                separated_list0(multispace1, parse_robot_cost),
+                Blueprint::new,
+            )),
         ),
-        |(i, robot_costs_vec)| {
-            (
-                i as i32,
-                Blueprint {
-                    costs: robot_costs_vec.into_iter().collect(),
-                },
-            )
-        },
+        |(i, blueprint)| (i as i32, blueprint),
     )(i)
 }
 
 fn parse_blueprints(input: &str) -> Result<BTreeMap<i32, Blueprint>> {
     Ok(
-        parse_nice(input, separated_list0(multispace1, parse_blueprint))
-            .ok_or(anyhow!("Error parsing input"))?
+        parse_input(input, separated_list0(multispace1, parse_blueprint))?
             .into_iter()
             .collect(),
     )
 }
 
-fn get_blueprint_quality(blueprint: &Blueprint) -> u32 {
-    let mut states = BTreeSet::from_iter([State::new()]);
-    let mut max_geodes = 0;
-
-    while !states.is_empty() {
-        //println!("Num states: {}, max_geodes: {max_geodes}", states.len());
-        let next_state = states.pop_last().unwrap();
-
-        let geode_count = next_state.materials[Material::Geode as usize];
-        //println!("count: {geode_count}");
-        println!("Next state: {next_state:#?}, count: {geode_count}");
-        if next_state.minute == 24 {
-            max_geodes = max_geodes.max(geode_count);
-        } else {
-            //states.extend(next_state.get_next(blueprint))
-            states.insert(State::get_best_next(vec![next_state], blueprint));
+pub fn best_schedule(blueprint: &Blueprint, minutes: i32) -> Schedule {
+    // Only one robot can be built per minute, so there's no point in collecting more of
+    // a material per minute than the most any robot costs
+    let mut max_incomes = vec![0; blueprint.materials.len()];
+    for robot in blueprint.recipes.iter() {
+        for (m, cost) in blueprint.costs(*robot) {
+            max_incomes[*m] = max_incomes[*m].max(*cost);
         }
     }
+    max_incomes[blueprint.target] = i32::MAX;
+
+    let mut best = (0, vec![]);
+    State::new(blueprint).search(blueprint, minutes, &max_incomes, &mut vec![], &mut best);
+
+    Schedule::replay(blueprint, minutes, &best.1)
+}
 
-    max_geodes as u32
+/// Reported every time a blueprint's best schedule is found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlueprintProgress {
+    pub id: i32,
+    pub collected: i32,
+    pub done: usize,
+    pub total: usize,
+}
+
+/// Finds the best schedule of every blueprint in parallel. Results are keyed by
+/// blueprint id regardless of the order they finish in, `on_progress` is called (from
+/// the worker threads) as each one does.
+pub fn best_schedules<'a, F>(
+    blueprints: impl IntoIterator<Item = (&'a i32, &'a Blueprint)>,
+    minutes: i32,
+    on_progress: F,
+) -> BTreeMap<i32, Schedule>
+where
+    F: Fn(BlueprintProgress) + Sync,
+{
+    let blueprints = blueprints.into_iter().collect::<Vec<_>>();
+    let total = blueprints.len();
+    let done = AtomicUsize::new(0);
+
+    blueprints
+        .into_par_iter()
+        .map(|(id, blueprint)| {
+            let schedule = best_schedule(blueprint, minutes);
+            on_progress(BlueprintProgress {
+                id: *id,
+                collected: schedule.collected,
+                done: done.fetch_add(1, atomic::Ordering::Relaxed) + 1,
+                total,
+            });
+            (*id, schedule)
+        })
+        .collect()
+}
+
+pub fn get_blueprints(input: &str) -> Result<BTreeMap<i32, Blueprint>> {
+    parse_blueprints(input)
+}
+
+fn sum_quality_levels(blueprints: &BTreeMap<i32, Blueprint>, minutes: i32) -> u32 {
+    best_schedules(blueprints, minutes, |_| {})
+        .iter()
+        .map(|(id, schedule)| (id * schedule.collected) as u32)
+        .sum()
+}
+
+fn max_geodes_product(blueprints: &BTreeMap<i32, Blueprint>, count: usize, minutes: i32) -> u32 {
+    best_schedules(blueprints.iter().take(count), minutes, |_| {})
+        .values()
+        .map(|schedule| schedule.collected as u32)
+        .product()
 }
 
 pub fn get_sum_quality_levels(input: &str) -> Result<u32> {
-    let blueprints = parse_blueprints(input)?;
+    Ok(sum_quality_levels(&parse_blueprints(input)?, 24))
+}
+
+pub fn get_max_geodes_product(input: &str) -> Result<u32> {
+    Ok(max_geodes_product(&parse_blueprints(input)?, 3, 32))
+}
+
+pub fn get_schedules<F>(
+    input: &str,
+    minutes: i32,
+    on_progress: F,
+) -> Result<BTreeMap<i32, Schedule>>
+where
+    F: Fn(BlueprintProgress) + Sync,
+{
+    Ok(best_schedules(
+        &parse_blueprints(input)?,
+        minutes,
+        on_progress,
+    ))
+}
+
+pub struct Day19;
+
+impl Solver for Day19 {
+    type Input = BTreeMap<i32, Blueprint>;
+    type Answer1 = u32;
+    type Answer2 = u32;
 
-    for (_, blueprint) in blueprints.iter() {
-        println!("{}", get_blueprint_quality(blueprint))
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_blueprints(input)
     }
 
-    Ok(0)
+    fn part1(blueprints: &Self::Input) -> Result<Self::Answer1> {
+        Ok(sum_quality_levels(blueprints, 24))
+    }
+
+    fn part2(blueprints: &Self::Input) -> Result<Self::Answer2> {
+        Ok(max_geodes_product(blueprints, 3, 32))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::Mutex;
 
     const TEST_INPUT: &str = include_str!("../data/test_input");
 
@@ -398,4 +498,103 @@ mod tests {
         assert!(res.is_ok());
         assert_eq!(res.unwrap(), 33);
     }
+
+    #[test]
+    fn part2() {
+        let res = get_max_geodes_product(TEST_INPUT);
+        assert!(res.is_ok());
+        assert_eq!(res.unwrap(), 56 * 62);
+    }
+
+    #[test]
+    fn schedule() {
+        let progress = Mutex::new(vec![]);
+        let res = get_schedules(TEST_INPUT, 24, |p| progress.lock().unwrap().push(p));
+        assert!(res.is_ok());
+        let mut progress = progress.into_inner().unwrap();
+        progress.sort_by_key(|p| p.done);
+        assert_eq!(
+            progress
+                .iter()
+                .map(|p| (p.done, p.total))
+                .collect::<Vec<_>>(),
+            vec![(1, 2), (2, 2)]
+        );
+        assert!(progress.iter().any(|p| p.id == 2 && p.collected == 12));
+
+        let schedules = res.unwrap();
+        assert_eq!(schedules[&1].collected, 9);
+        assert_eq!(schedules[&2].collected, 12);
+
+        let first_geode_robot = schedules[&1]
+            .builds
+            .iter()
+            .find(|build| build.robot == "geode")
+            .unwrap();
+        assert_eq!(first_geode_robot.minute, 18);
+        assert!(schedules[&1]
+            .to_string()
+            .ends_with("Minute 24: end -> materials: 6 ore, 41 clay, 8 obsidian, 9 geode; robots: 1 ore, 4 clay, 2 obsidian, 2 geode\n9 geode collected"));
+    }
+
+    /// Tries every choice, every minute.
+    fn brute_force(state: State, blueprint: &Blueprint, minutes: i32) -> i32 {
+        if state.minute == minutes {
+            return state.materials[blueprint.target];
+        }
+
+        blueprint
+            .recipes
+            .iter()
+            .filter(|robot| {
+                blueprint
+                    .costs(**robot)
+                    .iter()
+                    .all(|(m, cost)| state.materials[*m] >= *cost)
+            })
+            .map(|robot| {
+                let next = state.clone().progress(1).build_robot(blueprint, *robot);
+                brute_force(next, blueprint, minutes)
+            })
+            .chain(std::iter::once(brute_force(
+                state.clone().progress(1),
+                blueprint,
+                minutes,
+            )))
+            .max()
+            .unwrap()
+    }
+
+    #[test]
+    fn custom_materials() {
+        let res = get_blueprints(
+            "Blueprint 1:
+  Each wood robot costs 1 wood.
+  Each plank robot costs 2 wood.
+  Each chair robot costs 2 wood and 2 plank.",
+        );
+        assert!(res.is_ok());
+        let blueprint = res.unwrap().remove(&1).unwrap();
+        assert_eq!(blueprint.materials(), ["wood", "plank", "chair"]);
+        assert_eq!(blueprint.target(), "chair");
+
+        for minutes in 1..=9 {
+            let expected = brute_force(State::new(&blueprint), &blueprint, minutes);
+            assert_eq!(best_schedule(&blueprint, minutes).collected, expected);
+        }
+
+        let blueprint = blueprint.with_target("plank").unwrap();
+        let expected = brute_force(State::new(&blueprint), &blueprint, 9);
+        assert_eq!(best_schedule(&blueprint, 9).collected, expected);
+    }
+
+    #[test]
+    fn duplicate_robot() {
+        let res =
+            get_blueprints("Blueprint 1: Each ore robot costs 1 ore. Each ore robot costs 2 ore.");
+        assert!(res.is_err());
+        let report = res.unwrap_err().downcast::<util::ParseReport>().unwrap();
+        assert_eq!((report.line(), report.column()), (1, 14));
+        assert!(report.render().contains("Robot for ore is listed twice"));
+    }
 }
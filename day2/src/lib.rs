@@ -0,0 +1,185 @@
+#![feature(try_blocks)]
+
+use anyhow::{anyhow, Result};
+use thiserror::Error;
+use util::Solver;
+
+#[derive(Debug)]
+enum MatchResult {
+    Lose,
+    Draw,
+    Win,
+}
+
+impl MatchResult {
+    fn value(self: &Self) -> i32 {
+        match self {
+            Self::Lose => 0,
+            Self::Draw => 3,
+            Self::Win => 6,
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+#[error("Error parsing into MatchResult")]
+struct MatchResultParseError;
+
+impl TryFrom<&str> for MatchResult {
+    type Error = MatchResultParseError;
+
+    fn try_from(shape: &str) -> Result<Self, Self::Error> {
+        if shape.len() != 1 {
+            return Err(MatchResultParseError);
+        }
+
+        match shape {
+            "X" => Ok(Self::Lose),
+            "Y" => Ok(Self::Draw),
+            "Z" => Ok(Self::Win),
+            _ => Err(MatchResultParseError),
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+enum Shape {
+    Rock,
+    Paper,
+    Scissors,
+}
+
+#[derive(Error, Debug)]
+#[error("Error parsing into Shape")]
+struct ShapeParseError;
+
+impl TryFrom<&str> for Shape {
+    type Error = ShapeParseError;
+
+    fn try_from(shape: &str) -> Result<Self, Self::Error> {
+        if shape.len() != 1 {
+            return Err(ShapeParseError);
+        }
+
+        match shape {
+            "A" | "X" => Ok(Self::Rock),
+            "B" | "Y" => Ok(Self::Paper),
+            "C" | "Z" => Ok(Self::Scissors),
+            _ => Err(ShapeParseError),
+        }
+    }
+}
+
+impl Shape {
+    fn value(self: &Self) -> i32 {
+        match self {
+            Self::Rock => 1,
+            Self::Paper => 2,
+            Self::Scissors => 3,
+        }
+    }
+
+    fn match_result(shape1: &Self, shape2: &Self) -> MatchResult {
+        match (shape1, shape2) {
+            (Self::Rock, Self::Paper)
+            | (Self::Paper, Self::Scissors)
+            | (Self::Scissors, Self::Rock) => MatchResult::Win,
+            (s1, s2) if s1 == s2 => MatchResult::Draw,
+            _ => MatchResult::Lose,
+        }
+    }
+
+    fn from_result(shape1: &Self, result: &MatchResult) -> Self {
+        match (shape1, result) {
+            (s, MatchResult::Draw) => *s,
+            (Self::Rock, MatchResult::Lose) | (Self::Paper, MatchResult::Win) => Self::Scissors,
+            (Self::Paper, MatchResult::Lose) | (Self::Scissors, MatchResult::Win) => Self::Rock,
+            (Self::Scissors, MatchResult::Lose) | (Self::Rock, MatchResult::Win) => Self::Paper,
+        }
+    }
+
+    fn match_value(shape1: &Shape, shape2: &Shape) -> i32 {
+        Self::match_result(shape1, shape2).value() + shape2.value()
+    }
+}
+
+/// A line of the strategy guide. What the second column means depends on the part,
+/// so both readings are kept.
+#[derive(Debug)]
+pub struct Round {
+    opponent: Shape,
+    response: Shape,
+    result: MatchResult,
+}
+
+impl TryFrom<&str> for Round {
+    type Error = anyhow::Error;
+
+    fn try_from(line: &str) -> Result<Self> {
+        let [opponent, second]: [&str; 2] = line
+            .split(' ')
+            .collect::<Vec<_>>()
+            .try_into()
+            .map_err(|e: Vec<_>| anyhow!("wrong number of shapes: {}", e.len()))?;
+
+        Ok(Round {
+            opponent: opponent.try_into()?,
+            response: second.try_into()?,
+            result: second.try_into()?,
+        })
+    }
+}
+
+pub fn parse_rounds(input: impl Iterator<Item = impl Into<String>>) -> Result<Vec<Round>> {
+    input
+        .map(|line| {
+            let line: String = line.into();
+            Round::try_from(line.as_str())
+        })
+        .collect()
+}
+
+fn total(rounds: &[Round]) -> i32 {
+    rounds
+        .iter()
+        .map(|round| Shape::match_value(&round.opponent, &round.response))
+        .sum()
+}
+
+fn total_2(rounds: &[Round]) -> i32 {
+    rounds
+        .iter()
+        .map(|round| {
+            let response = Shape::from_result(&round.opponent, &round.result);
+            Shape::match_value(&round.opponent, &response)
+        })
+        .sum()
+}
+
+pub fn find_total(input: impl Iterator<Item = impl Into<String>>) -> Result<i32> {
+    Ok(total(&parse_rounds(input)?))
+}
+
+pub fn find_total_2(input: impl Iterator<Item = impl Into<String>>) -> Result<i32> {
+    Ok(total_2(&parse_rounds(input)?))
+}
+
+pub struct Day2;
+
+impl Solver for Day2 {
+    type Input = Vec<Round>;
+    type Answer1 = i32;
+    type Answer2 = i32;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_rounds(input.lines())
+    }
+
+    fn part1(rounds: &Self::Input) -> Result<Self::Answer1> {
+        Ok(total(rounds))
+    }
+
+    fn part2(rounds: &Self::Input) -> Result<Self::Answer2> {
+        Ok(total_2(rounds))
+    }
+}
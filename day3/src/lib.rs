@@ -0,0 +1,181 @@
+#![feature(try_blocks)]
+
+use anyhow::{anyhow, Result};
+use itertools::Itertools;
+use std::collections::BTreeSet;
+use util::Solver;
+
+fn get_value(c: char) -> Option<i32> {
+    match c as u32 {
+        i if (65..=90).contains(&i) => Some((i - 38) as i32),
+        i if (97..=122).contains(&i) => Some((i - 96) as i32),
+        _ => None,
+    }
+}
+
+fn get_same(arrs: &[Vec<i32>]) -> Option<i32> {
+    let mut inds = vec![0_usize; arrs.len()];
+    let mut value = None;
+
+    while let Some(values) = arrs
+        .iter()
+        .zip(inds.iter())
+        .map(|(arr, i)| arr.get(*i).copied())
+        .collect::<Option<Vec<i32>>>()
+    {
+        if values.windows(2).all(|w| w[0] == w[1]) {
+            value = values.first().copied();
+            break;
+        } else {
+            if let Some(index_of_min) = values
+                .iter()
+                .enumerate()
+                .min_by(|(_, a), (_, b)| a.cmp(b))
+                .map(|(index, _)| index)
+            {
+                if let Some(ind) = inds.get_mut(index_of_min) {
+                    *ind += 1;
+                }
+            }
+        }
+    }
+
+    value
+}
+
+/// Item priorities of a rucksack, in the order the items are listed.
+#[derive(Debug)]
+pub struct Rucksack {
+    priorities: Vec<i32>,
+}
+
+impl TryFrom<&str> for Rucksack {
+    type Error = anyhow::Error;
+
+    fn try_from(line: &str) -> Result<Self> {
+        let priorities = line
+            .chars()
+            .map(|c| get_value(c).ok_or(anyhow!("Unrecognized item type: {c}")))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Rucksack { priorities })
+    }
+}
+
+impl Rucksack {
+    fn compartments(&self) -> Result<[Vec<i32>; 2]> {
+        if self.priorities.len() % 2 != 0 {
+            Err(anyhow!(
+                "Number of characters in a line is not divisible by 2"
+            ))?
+        }
+        self.priorities
+            .chunks(self.priorities.len() / 2)
+            .map(sorted_types)
+            .collect::<Vec<_>>()
+            .try_into()
+            .map_err(|e: Vec<_>| anyhow!("wrong number of chunks: {}", e.len()))
+    }
+}
+
+fn sorted_types(priorities: &[i32]) -> Vec<i32> {
+    priorities
+        .iter()
+        .copied()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+pub fn parse_rucksacks(input: impl Iterator<Item = impl Into<String>>) -> Result<Vec<Rucksack>> {
+    input
+        .map(|line| {
+            let line: String = line.into();
+            Rucksack::try_from(line.as_str())
+        })
+        .collect()
+}
+
+fn sum_types(rucksacks: &[Rucksack]) -> Result<i32> {
+    rucksacks
+        .iter()
+        .map(|rucksack| try {
+            get_same(&rucksack.compartments()?).ok_or(anyhow!("Missing a singular common type!"))?
+        })
+        .sum()
+}
+
+fn sum_badges(rucksacks: &[Rucksack]) -> Result<i32> {
+    rucksacks
+        .iter()
+        .map(|rucksack| sorted_types(&rucksack.priorities))
+        .tuples()
+        .map(|(ln1, ln2, ln3)| try {
+            get_same(&[ln1, ln2, ln3]).ok_or(anyhow!("Missing a singular common type!"))?
+        })
+        .sum()
+}
+
+pub fn find_sum_types(input: impl Iterator<Item = impl Into<String>>) -> Result<i32> {
+    sum_types(&parse_rucksacks(input)?)
+}
+
+pub fn find_sum_badges(input: impl Iterator<Item = impl Into<String>>) -> Result<i32> {
+    sum_badges(&parse_rucksacks(input)?)
+}
+
+pub struct Day3;
+
+impl Solver for Day3 {
+    type Input = Vec<Rucksack>;
+    type Answer1 = i32;
+    type Answer2 = i32;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_rucksacks(input.lines())
+    }
+
+    fn part1(rucksacks: &Self::Input) -> Result<Self::Answer1> {
+        sum_types(rucksacks)
+    }
+
+    fn part2(rucksacks: &Self::Input) -> Result<Self::Answer2> {
+        sum_badges(rucksacks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_input() -> String {
+        r"vJrwpWtwJgWrhcsFMMfFFhFp
+jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+PmmdzqPrVvPwwTWBwg
+wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+ttgJtRGJQctTZtZT
+CrZsJsPPZsGzwwsLwLmpwMDw"
+            .to_string()
+    }
+
+    #[test]
+    fn sum_types_ok() {
+        let test_input = test_input();
+
+        let total = find_sum_types(test_input.lines());
+
+        assert!(total.is_ok());
+
+        assert_eq!(total.unwrap(), 157);
+    }
+
+    #[test]
+    fn sum_badges_ok() {
+        let test_input = test_input();
+
+        let total = find_sum_badges(test_input.lines());
+
+        assert!(total.is_ok());
+
+        assert_eq!(total.unwrap(), 70);
+    }
+}
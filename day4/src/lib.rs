@@ -1,14 +1,9 @@
 #![feature(try_blocks)]
 
 use anyhow::{anyhow, Result};
+use util::Solver;
 
-fn num_overlap_condition<F>(
-    input: impl Iterator<Item = impl Into<String>>,
-    condition_true: F,
-) -> Result<i32>
-where
-    F: Fn(&[i32; 4]) -> bool,
-{
+fn parse_ranges(input: impl Iterator<Item = impl Into<String>>) -> Result<Vec<[i32; 4]>> {
     input
         .map(|line| try {
             let line: String = line.into();
@@ -22,12 +17,18 @@ where
                     anyhow!("wrong number of range starts/ends in a line: {}", e.len())
                 })?;
 
-            if condition_true(&ranges) {
-                1
-            } else {
-                0
-            }
+            ranges
         })
+        .collect()
+}
+
+fn num_overlap_condition<F>(ranges: &[[i32; 4]], condition_true: F) -> i32
+where
+    F: Fn(&[i32; 4]) -> bool,
+{
+    ranges
+        .iter()
+        .map(|ranges| if condition_true(ranges) { 1 } else { 0 })
         .sum()
 }
 
@@ -43,11 +44,37 @@ fn condition_any_overlap(ranges: &[i32; 4]) -> bool {
 }
 
 pub fn num_overlap_full(input: impl Iterator<Item = impl Into<String>>) -> Result<i32> {
-    num_overlap_condition(input, condition_full_overlap)
+    Ok(num_overlap_condition(
+        &parse_ranges(input)?,
+        condition_full_overlap,
+    ))
 }
 
 pub fn num_overlap_any(input: impl Iterator<Item = impl Into<String>>) -> Result<i32> {
-    num_overlap_condition(input, condition_any_overlap)
+    Ok(num_overlap_condition(
+        &parse_ranges(input)?,
+        condition_any_overlap,
+    ))
+}
+
+pub struct Day4;
+
+impl Solver for Day4 {
+    type Input = Vec<[i32; 4]>;
+    type Answer1 = i32;
+    type Answer2 = i32;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_ranges(input.lines())
+    }
+
+    fn part1(ranges: &Self::Input) -> Result<Self::Answer1> {
+        Ok(num_overlap_condition(ranges, condition_full_overlap))
+    }
+
+    fn part2(ranges: &Self::Input) -> Result<Self::Answer2> {
+        Ok(num_overlap_condition(ranges, condition_any_overlap))
+    }
 }
 
 #[cfg(test)]
@@ -5,15 +5,16 @@
 use anyhow::Result;
 use std::{collections::VecDeque, str::FromStr};
 use thiserror::Error;
+use util::Solver;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub struct Move {
     n: i32,
     from: usize,
     to: usize,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Stacks {
     stacks: Vec<VecDeque<char>>,
 }
@@ -158,24 +159,49 @@ pub fn get_stacks_and_moves(
     Ok((stacks, moves))
 }
 
-pub fn move_and_get_top_9000(input: impl Iterator<Item = impl Into<String>>) -> Result<String> {
-    let (mut stacks, moves) = get_stacks_and_moves(input)?;
+fn move_and_get_top<F>(stacks: &Stacks, moves: &[Move], mut apply_move: F) -> Result<String>
+where
+    F: FnMut(&mut Stacks, Move) -> Result<(), MoveError>,
+{
+    let mut stacks = stacks.clone();
 
-    for mv in moves.into_iter() {
-        stacks.apply_move(mv)?;
+    for mv in moves.iter() {
+        apply_move(&mut stacks, *mv)?;
     }
 
     Ok(stacks.top_crates())
 }
 
+pub fn move_and_get_top_9000(input: impl Iterator<Item = impl Into<String>>) -> Result<String> {
+    let (stacks, moves) = get_stacks_and_moves(input)?;
+
+    move_and_get_top(&stacks, &moves, Stacks::apply_move)
+}
+
 pub fn move_and_get_top_9001(input: impl Iterator<Item = impl Into<String>>) -> Result<String> {
-    let (mut stacks, moves) = get_stacks_and_moves(input)?;
+    let (stacks, moves) = get_stacks_and_moves(input)?;
+
+    move_and_get_top(&stacks, &moves, Stacks::apply_move_crate_mover9001)
+}
 
-    for mv in moves.into_iter() {
-        stacks.apply_move_crate_mover9001(mv)?;
+pub struct Day5;
+
+impl Solver for Day5 {
+    type Input = (Stacks, Vec<Move>);
+    type Answer1 = String;
+    type Answer2 = String;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        get_stacks_and_moves(input.lines())
     }
 
-    Ok(stacks.top_crates())
+    fn part1((stacks, moves): &Self::Input) -> Result<Self::Answer1> {
+        move_and_get_top(stacks, moves, Stacks::apply_move)
+    }
+
+    fn part2((stacks, moves): &Self::Input) -> Result<Self::Answer2> {
+        move_and_get_top(stacks, moves, Stacks::apply_move_crate_mover9001)
+    }
 }
 
 #[cfg(test)]
@@ -1,6 +1,7 @@
 use std::collections::BTreeSet;
 
 use anyhow::{anyhow, Result};
+use util::Solver;
 
 pub fn start_n(signal: String, n: usize) -> Result<usize> {
     signal
@@ -12,6 +13,30 @@ pub fn start_n(signal: String, n: usize) -> Result<usize> {
         .ok_or(anyhow!("not found"))
 }
 
+pub struct Day6;
+
+impl Solver for Day6 {
+    type Input = String;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        input
+            .lines()
+            .next()
+            .map(|l| l.to_string())
+            .ok_or(anyhow!("No line in input"))
+    }
+
+    fn part1(signal: &Self::Input) -> Result<Self::Answer1> {
+        start_n(signal.clone(), 4)
+    }
+
+    fn part2(signal: &Self::Input) -> Result<Self::Answer2> {
+        start_n(signal.clone(), 14)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,6 +1,8 @@
 use std::iter;
 
+use anyhow::Result;
 use itertools::Itertools;
+use util::Solver;
 
 #[derive(Debug)]
 pub enum Command {
@@ -178,8 +180,7 @@ impl Node {
     }
 }
 
-pub fn size_smallest(input: impl Iterator<Item = String>, biggest: usize) -> usize {
-    let root = Node::parse(input);
+fn sum_sizes_smaller(root: &Node, biggest: usize) -> usize {
     let dirs_with_sizes = root.get_sizes();
 
     dirs_with_sizes
@@ -188,8 +189,7 @@ pub fn size_smallest(input: impl Iterator<Item = String>, biggest: usize) -> usi
         .sum()
 }
 
-pub fn size_to_delete(input: impl Iterator<Item = String>, total: usize, needed: usize) -> usize {
-    let root = Node::parse(input);
+fn smallest_to_delete(root: &Node, total: usize, needed: usize) -> usize {
     let sizes = root
         .get_sizes()
         .into_iter()
@@ -214,6 +214,34 @@ pub fn size_to_delete(input: impl Iterator<Item = String>, total: usize, needed:
         .expect("No directory to free found")
 }
 
+pub fn size_smallest(input: impl Iterator<Item = String>, biggest: usize) -> usize {
+    sum_sizes_smaller(&Node::parse(input), biggest)
+}
+
+pub fn size_to_delete(input: impl Iterator<Item = String>, total: usize, needed: usize) -> usize {
+    smallest_to_delete(&Node::parse(input), total, needed)
+}
+
+pub struct Day7;
+
+impl Solver for Day7 {
+    type Input = Node;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(Node::parse(input.lines().map(|l| l.to_string())))
+    }
+
+    fn part1(root: &Self::Input) -> Result<Self::Answer1> {
+        Ok(sum_sizes_smaller(root, 100000))
+    }
+
+    fn part2(root: &Self::Input) -> Result<Self::Answer2> {
+        Ok(smallest_to_delete(root, 70000000, 30000000))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
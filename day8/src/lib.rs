@@ -4,6 +4,7 @@ use std::{cmp::Ordering, collections::HashMap};
 
 use anyhow::{anyhow, Context, Result};
 use num::range_step_inclusive;
+use util::Solver;
 
 fn get_trees(input: impl Iterator<Item = String>) -> Result<Vec<Vec<usize>>> {
     let trees = input
@@ -71,15 +72,14 @@ where
     Ok(())
 }
 
-fn get_scenic_scores(input: impl Iterator<Item = String>) -> Result<Vec<Vec<usize>>> {
-    let trees = get_trees(input)?;
-    let n = get_n_with_check(&trees)?;
+fn get_scenic_scores(trees: &Vec<Vec<usize>>) -> Result<Vec<Vec<usize>>> {
+    let n = get_n_with_check(trees)?;
 
     let mut scenic_scores = vec![vec!(1; n); n];
 
     let mut current_scores = HashMap::new();
 
-    run_all_directions(&trees, |(row, col, val, new_outer)| {
+    run_all_directions(trees, |(row, col, val, new_outer)| {
         if new_outer {
             current_scores.clear();
         }
@@ -103,8 +103,8 @@ fn get_scenic_scores(input: impl Iterator<Item = String>) -> Result<Vec<Vec<usiz
     Ok(scenic_scores)
 }
 
-pub fn get_greatest_scenic_score(input: impl Iterator<Item = String>) -> Result<usize> {
-    get_scenic_scores(input)?
+fn greatest_scenic_score(trees: &Vec<Vec<usize>>) -> Result<usize> {
+    get_scenic_scores(trees)?
         .iter()
         .flatten()
         .max()
@@ -112,15 +112,13 @@ pub fn get_greatest_scenic_score(input: impl Iterator<Item = String>) -> Result<
         .context("No scenic scores!")
 }
 
-pub fn get_num_visible(input: impl Iterator<Item = String>) -> Result<usize> {
-    let trees = get_trees(input)?;
-
-    let n = get_n_with_check(&trees)?;
+fn num_visible(trees: &Vec<Vec<usize>>) -> Result<usize> {
+    let n = get_n_with_check(trees)?;
 
     let mut visible = vec![vec!(false; n); n];
     let mut current_biggest = None;
 
-    run_all_directions(&trees, |(row, col, val, new_outer)| {
+    run_all_directions(trees, |(row, col, val, new_outer)| {
         if new_outer {
             current_biggest = None;
         }
@@ -151,6 +149,34 @@ pub fn get_num_visible(input: impl Iterator<Item = String>) -> Result<usize> {
         .sum())
 }
 
+pub fn get_greatest_scenic_score(input: impl Iterator<Item = String>) -> Result<usize> {
+    greatest_scenic_score(&get_trees(input)?)
+}
+
+pub fn get_num_visible(input: impl Iterator<Item = String>) -> Result<usize> {
+    num_visible(&get_trees(input)?)
+}
+
+pub struct Day8;
+
+impl Solver for Day8 {
+    type Input = Vec<Vec<usize>>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        get_trees(input.lines().map(|l| l.to_string()))
+    }
+
+    fn part1(trees: &Self::Input) -> Result<Self::Answer1> {
+        num_visible(trees)
+    }
+
+    fn part2(trees: &Self::Input) -> Result<Self::Answer2> {
+        greatest_scenic_score(trees)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
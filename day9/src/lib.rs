@@ -8,9 +8,10 @@ use std::{
 };
 
 use anyhow::{anyhow, Result};
+use util::Solver;
 
 #[derive(Copy, Clone, Debug, PartialEq)]
-enum Move {
+pub enum Move {
     Left(i32),
     Right(i32),
     Up(i32),
@@ -96,13 +97,14 @@ impl Move {
     }
 }
 
-pub fn count_unique_tail_positions(
-    input: impl Iterator<Item = String>,
-    rope_len: usize,
-) -> Result<usize> {
-    let t_pos = input
-        .flat_map(|l| {
-            let mv = l.parse::<Move>().unwrap();
+fn parse_moves(input: impl Iterator<Item = String>) -> Result<Vec<Move>> {
+    input.map(|l| l.parse::<Move>()).collect()
+}
+
+fn unique_tail_positions(moves: &[Move], rope_len: usize) -> usize {
+    let t_pos = moves
+        .iter()
+        .flat_map(|mv| {
             let len = mv.len();
             repeat(mv.with(1)).take(len as usize)
         })
@@ -114,7 +116,34 @@ pub fn count_unique_tail_positions(
         .chain(iter::once((0, 0)))
         .collect::<HashSet<_>>();
 
-    Ok(t_pos.len())
+    t_pos.len()
+}
+
+pub fn count_unique_tail_positions(
+    input: impl Iterator<Item = String>,
+    rope_len: usize,
+) -> Result<usize> {
+    Ok(unique_tail_positions(&parse_moves(input)?, rope_len))
+}
+
+pub struct Day9;
+
+impl Solver for Day9 {
+    type Input = Vec<Move>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_moves(input.lines().map(|l| l.to_string()))
+    }
+
+    fn part1(moves: &Self::Input) -> Result<Self::Answer1> {
+        Ok(unique_tail_positions(moves, 2))
+    }
+
+    fn part2(moves: &Self::Input) -> Result<Self::Answer2> {
+        Ok(unique_tail_positions(moves, 10))
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,214 @@
+//! Puzzle inputs are looked up in a cache directory (`<dir>/<year>/day<day>`) and only
+//! fetched, then cached, when they aren't there yet.
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Context, Result};
+
+/// Overrides the default cache directory.
+pub const CACHE_DIR_VAR: &str = "AOC_CACHE_DIR";
+/// Session cookie used to download inputs from adventofcode.com.
+pub const SESSION_VAR: &str = "AOC_SESSION";
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+pub trait Fetcher {
+    fn fetch(&self, year: u32, day: u32) -> Result<String>;
+}
+
+#[derive(Debug, Clone)]
+pub struct HttpFetcher {
+    base_url: String,
+    session: Option<String>,
+}
+
+impl HttpFetcher {
+    pub fn new(session: Option<String>) -> Self {
+        HttpFetcher {
+            base_url: DEFAULT_BASE_URL.to_string(),
+            session,
+        }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(env::var(SESSION_VAR).ok())
+    }
+
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+}
+
+impl Fetcher for HttpFetcher {
+    fn fetch(&self, year: u32, day: u32) -> Result<String> {
+        let session = self.session.as_ref().ok_or(anyhow!(
+            "No session set, export {SESSION_VAR} to fetch inputs"
+        ))?;
+        let url = format!(
+            "{}/{year}/day/{day}/input",
+            self.base_url.trim_end_matches('/')
+        );
+
+        ureq::get(&url)
+            .set("Cookie", &format!("session={session}"))
+            .call()
+            .with_context(|| format!("Unable to fetch {url}"))?
+            .into_string()
+            .with_context(|| format!("Unable to read response from {url}"))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct InputCache<F> {
+    dir: PathBuf,
+    fetcher: F,
+}
+
+impl<F: Fetcher> InputCache<F> {
+    pub fn new(dir: impl Into<PathBuf>, fetcher: F) -> Self {
+        InputCache {
+            dir: dir.into(),
+            fetcher,
+        }
+    }
+
+    /// Uses `$AOC_CACHE_DIR`, falling back to `~/.cache/aoc`.
+    pub fn from_env(fetcher: F) -> Self {
+        let dir = match env::var_os(CACHE_DIR_VAR) {
+            Some(dir) => PathBuf::from(dir),
+            None => env::var_os("HOME")
+                .map_or_else(|| PathBuf::from("."), PathBuf::from)
+                .join(".cache")
+                .join("aoc"),
+        };
+
+        Self::new(dir, fetcher)
+    }
+
+    pub fn dir(&self) -> &Path {
+        self.dir.as_path()
+    }
+
+    pub fn path(&self, year: u32, day: u32) -> PathBuf {
+        self.dir.join(year.to_string()).join(format!("day{day}"))
+    }
+
+    pub fn get(&self, year: u32, day: u32) -> Result<String> {
+        let path = self.path(year, day);
+        if path.exists() {
+            return fs::read_to_string(&path)
+                .with_context(|| format!("Unable to read cached input {}", path.display()));
+        }
+
+        let input = self.fetcher.fetch(year, day)?;
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Unable to create {}", parent.display()))?;
+        }
+        fs::write(&path, &input)
+            .with_context(|| format!("Unable to cache input to {}", path.display()))?;
+
+        Ok(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        cell::Cell,
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+        process, thread,
+    };
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("aoc-input-{}-{name}", process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    /// Serves a single request, answering with `body` if it asks for the expected
+    /// path with the expected session cookie.
+    fn fixture_server(body: &'static str) -> (String, thread::JoinHandle<()>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let request = BufReader::new(&stream)
+                .lines()
+                .map(|l| l.unwrap())
+                .take_while(|l| !l.is_empty())
+                .collect::<Vec<_>>();
+
+            let ok = request[0].starts_with("GET /2022/day/3/input ")
+                && request.iter().any(|l| l == "Cookie: session=secret");
+            let response = if ok {
+                format!(
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{body}",
+                    body.len()
+                )
+            } else {
+                "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n".to_string()
+            };
+            stream.write_all(response.as_bytes()).unwrap();
+        });
+
+        (base_url, handle)
+    }
+
+    #[test]
+    fn fetch_from_fixture_server() {
+        let (base_url, server) = fixture_server("vJrwpWtwJgWrhcsFMMfFFhFp\n");
+        let dir = temp_dir("http");
+        let cache = InputCache::new(
+            &dir,
+            HttpFetcher::new(Some("secret".to_string())).with_base_url(base_url),
+        );
+
+        let res = cache.get(2022, 3);
+        server.join().unwrap();
+        assert!(res.is_ok());
+        assert_eq!(res.unwrap(), "vJrwpWtwJgWrhcsFMMfFFhFp\n");
+        assert_eq!(
+            fs::read_to_string(dir.join("2022").join("day3")).unwrap(),
+            "vJrwpWtwJgWrhcsFMMfFFhFp\n"
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    struct CountingFetcher(Cell<u32>);
+
+    impl Fetcher for CountingFetcher {
+        fn fetch(&self, year: u32, day: u32) -> Result<String> {
+            self.0.set(self.0.get() + 1);
+            Ok(format!("{year}-{day}"))
+        }
+    }
+
+    #[test]
+    fn cached_input_is_not_refetched() {
+        let dir = temp_dir("cache");
+        let cache = InputCache::new(&dir, CountingFetcher(Cell::new(0)));
+
+        assert_eq!(cache.get(2022, 1).unwrap(), "2022-1");
+        assert_eq!(cache.get(2022, 1).unwrap(), "2022-1");
+        assert_eq!(cache.get(2021, 1).unwrap(), "2021-1");
+        assert_eq!(cache.fetcher.0.get(), 2);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn missing_session() {
+        let cache = InputCache::new(temp_dir("session"), HttpFetcher::new(None));
+        assert!(cache.get(2022, 1).is_err());
+    }
+}
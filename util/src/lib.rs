@@ -1,22 +1,25 @@
 #![feature(iter_intersperse)]
 
+pub mod input;
+
 use std::{
+    fmt::Display,
     io::{self, BufRead},
     str::FromStr,
 };
 
 use anyhow::Result;
-use miette::GraphicalReportHandler;
+use miette::{Diagnostic, GraphicalReportHandler, LabeledSpan, SourceCode, SourceSpan};
 use nom::{
-    character::complete::{char, digit1},
+    character::complete::{char, digit1, multispace0},
     combinator::{map_res, opt, recognize},
     error::ParseError,
-    sequence::tuple,
+    sequence::{terminated, tuple},
     IResult,
 };
 use nom_locate::LocatedSpan;
 use nom_supreme::{
-    error::{BaseErrorKind, ErrorTree, GenericErrorTree},
+    error::{ErrorTree, GenericErrorTree},
     final_parser::final_parser,
 };
 
@@ -24,16 +27,129 @@ use nom_supreme::{
 
 pub type Span<'a> = LocatedSpan<&'a str>;
 
-#[derive(thiserror::Error, Debug, miette::Diagnostic)]
-#[error("bad input")]
-struct BadInput<'a> {
-    #[source_code]
-    src: &'a str,
+/// A puzzle solution split into parsing and the two parts, so that every day can
+/// be run (and timed) the same way.
+pub trait Solver {
+    type Input;
+    type Answer1: Display;
+    type Answer2: Display;
+
+    fn parse(input: &str) -> Result<Self::Input>;
+
+    fn part1(input: &Self::Input) -> Result<Self::Answer1>;
+
+    fn part2(input: &Self::Input) -> Result<Self::Answer2>;
+}
+
+/// [`ErrorTree`] with locations turned into byte offsets, so it no longer borrows the input.
+pub type ParseErrorTree = GenericErrorTree<
+    usize,
+    &'static str,
+    &'static str,
+    Box<dyn std::error::Error + Send + Sync + 'static>,
+>;
+
+/// A failed parse together with the input it failed on. It can be propagated like any
+/// other error and rendered as a miette diagnostic wherever it ends up being reported.
+#[derive(thiserror::Error, Debug)]
+#[error("bad input at line {line}, column {column}")]
+pub struct ParseReport {
+    src: String,
+    span: SourceSpan,
+    line: usize,
+    column: usize,
+    #[source]
+    tree: ParseErrorTree,
+}
+
+impl ParseReport {
+    /// `tree` comes from parsing the part of `src` starting at byte `base`.
+    fn new(src: &str, tree: ErrorTree<Span>, base: usize) -> Self {
+        let tree = tree.map_locations(|location| base + location.location_offset());
+        let offset = furthest_location(&tree);
+        let line_start = src[..offset].rfind('\n').map_or(0, |i| i + 1);
+
+        ParseReport {
+            src: src.to_string(),
+            span: SourceSpan::new(offset.into(), 0.into()),
+            line: src[..line_start].matches('\n').count() + 1,
+            column: src[line_start..offset].chars().count() + 1,
+            tree,
+        }
+    }
+
+    pub fn src(&self) -> &str {
+        self.src.as_str()
+    }
+
+    /// Location of the error that got furthest into the input.
+    pub fn span(&self) -> SourceSpan {
+        self.span
+    }
+
+    /// 1-based line of [`ParseReport::span`] within the whole input.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// 1-based column (in characters) of [`ParseReport::span`] within its line.
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    pub fn tree(&self) -> &ParseErrorTree {
+        &self.tree
+    }
+
+    pub fn render(&self) -> String {
+        let mut s = String::new();
+        GraphicalReportHandler::new()
+            .with_context_lines(2)
+            .render_report(&mut s, self)
+            .unwrap();
+        s
+    }
+}
+
+impl Diagnostic for ParseReport {
+    fn source_code(&self) -> Option<&dyn SourceCode> {
+        Some(&self.src)
+    }
 
-    #[label("{kind}")]
-    bad_bit: miette::SourceSpan,
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+        let mut labels = vec![];
+        collect_labels(&self.tree, &mut labels);
+        Some(Box::new(labels.into_iter()))
+    }
+}
 
-    kind: BaseErrorKind<&'a str, Box<dyn std::error::Error + Send + Sync>>,
+fn furthest_location(tree: &ParseErrorTree) -> usize {
+    match tree {
+        GenericErrorTree::Base { location, .. } => *location,
+        GenericErrorTree::Stack { base, .. } => furthest_location(base),
+        GenericErrorTree::Alt(siblings) => {
+            siblings.iter().map(furthest_location).max().unwrap_or(0)
+        }
+    }
+}
+
+fn collect_labels(tree: &ParseErrorTree, labels: &mut Vec<LabeledSpan>) {
+    match tree {
+        GenericErrorTree::Base { location, kind } => {
+            labels.push(LabeledSpan::new(Some(kind.to_string()), *location, 0))
+        }
+        GenericErrorTree::Stack { base, contexts } => {
+            collect_labels(base, labels);
+            for (location, context) in contexts {
+                labels.push(LabeledSpan::new(Some(context.to_string()), *location, 0))
+            }
+        }
+        GenericErrorTree::Alt(siblings) => {
+            for sibling in siblings {
+                collect_labels(sibling, labels);
+            }
+        }
+    }
 }
 
 pub fn parse_number<'a, E>(i: Span<'a>) -> IResult<Span<'a>, i64, E>
@@ -45,35 +161,47 @@ where
     })(i)
 }
 
-pub fn parse_nice<'a, T, F>(l: &'a str, parse_fun: F) -> Option<T>
+/// Parses a single line, the whole of it has to be consumed.
+pub fn parse_nice<'a, T, F>(l: &'a str, parse_fun: F) -> Result<T, ParseReport>
 where
     F: FnMut(Span<'a>) -> IResult<Span<'a>, T, ErrorTree<Span<'a>>>,
 {
     let line_span = Span::new(l);
-    let line: Result<_, ErrorTree<Span>> = final_parser(parse_fun)(line_span);
-    match line {
-        Ok(line) => Some(line),
-        Err(e) => {
-            match e {
-                GenericErrorTree::Base { location, kind } => {
-                    let offset = location.location_offset().into();
-                    let err = BadInput {
-                        src: l,
-                        bad_bit: miette::SourceSpan::new(offset, 0.into()),
-                        kind,
-                    };
-                    let mut s = String::new();
-                    GraphicalReportHandler::new()
-                        .render_report(&mut s, &err)
-                        .unwrap();
-                    println!("{s}");
-                }
-                GenericErrorTree::Stack { .. } => todo!("stack"),
-                GenericErrorTree::Alt(_) => todo!("alt"),
-            }
-            None
+    final_parser(parse_fun)(line_span).map_err(|e: ErrorTree<Span>| ParseReport::new(l, e, 0))
+}
+
+/// Parses a whole puzzle input, allowing trailing whitespace. Errors point at the
+/// line and column within the full input rather than within a single line.
+pub fn parse_input<'a, T, F>(input: &'a str, parse_fun: F) -> Result<T, ParseReport>
+where
+    F: FnMut(Span<'a>) -> IResult<Span<'a>, T, ErrorTree<Span<'a>>>,
+{
+    let input_span = Span::new(input);
+    final_parser(terminated(parse_fun, multispace0))(input_span)
+        .map_err(|e: ErrorTree<Span>| ParseReport::new(input, e, 0))
+}
+
+/// Parses every non-blank line of a puzzle input on its own, each has to be consumed
+/// whole. Errors point at the line and column within the full input.
+pub fn parse_lines<'a, T, F>(input: &'a str, mut parse_fun: F) -> Result<Vec<T>, ParseReport>
+where
+    F: FnMut(Span<'a>) -> IResult<Span<'a>, T, ErrorTree<Span<'a>>>,
+{
+    let mut offset = 0;
+    let mut parsed = vec![];
+    for line in input.split_inclusive('\n') {
+        let base = offset;
+        offset += line.len();
+        let line = line.trim_end_matches(['\n', '\r']);
+        if line.trim().is_empty() {
+            continue;
         }
+        parsed.push(
+            final_parser(&mut parse_fun)(Span::new(line))
+                .map_err(|e: ErrorTree<Span>| ParseReport::new(input, e, base))?,
+        );
     }
+    Ok(parsed)
 }
 
 pub fn read_input_as_string() -> Result<String> {
@@ -86,3 +214,67 @@ pub fn read_input_as_string() -> Result<String> {
         .collect::<std::result::Result<String, _>>()
         .map_err(anyhow::Error::msg)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nom::{branch::alt, error::context, multi::separated_list0, sequence::preceded};
+    use nom_supreme::tag::complete::tag;
+
+    #[test]
+    fn parse_nice_ok() {
+        let res = parse_nice("-42", parse_number);
+        assert!(res.is_ok());
+        assert_eq!(res.unwrap(), -42);
+    }
+
+    #[test]
+    fn parse_nice_reports_stack() {
+        let res = parse_nice(
+            "x = abc",
+            context("assignment", preceded(tag("x = "), parse_number)),
+        );
+        assert!(res.is_err());
+        let report = res.unwrap_err();
+        assert_eq!(report.span().offset(), 4);
+        let labels = report.labels().unwrap().collect::<Vec<_>>();
+        assert_eq!(labels.len(), 2);
+        assert!(labels
+            .iter()
+            .any(|l| l.label() == Some("in section \"assignment\"")));
+    }
+
+    #[test]
+    fn parse_input_reports_line_and_column() {
+        let res = parse_input("1\n2\n3x\n4\n", separated_list0(char('\n'), parse_number));
+        assert!(res.is_err());
+        let report = res.unwrap_err();
+        assert_eq!((report.line(), report.column()), (3, 2));
+        assert_eq!(report.to_string(), "bad input at line 3, column 2");
+        assert!(report.render().contains("4\n"));
+    }
+
+    #[test]
+    fn parse_lines_reports_line_and_column() {
+        let res = parse_lines("1\n-2\n\n3\n", parse_number);
+        assert!(res.is_ok());
+        assert_eq!(res.unwrap(), vec![1, -2, 3]);
+
+        let res = parse_lines("1\r\n2\r\n\r\n4x\r\n5", parse_number);
+        assert!(res.is_err());
+        let report = res.unwrap_err();
+        assert_eq!((report.line(), report.column()), (4, 2));
+        assert_eq!(report.span().offset(), 9);
+        assert!(report.render().contains("4x"));
+    }
+
+    #[test]
+    fn parse_nice_reports_alt() {
+        let res = parse_nice("c", |i| alt((tag("a"), tag("b")))(i));
+        assert!(res.is_err());
+        let report = res.unwrap_err();
+        assert!(matches!(report.tree(), GenericErrorTree::Alt(_)));
+        assert_eq!(report.labels().unwrap().count(), 2);
+        assert!(report.render().contains("expected \"b\""));
+    }
+}